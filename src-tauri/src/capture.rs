@@ -24,6 +24,337 @@ pub struct CaptureTarget {
     pub width: u32,
     pub height: u32,
     pub thumbnail: String, // base64 JPEG (empty for now)
+    pub scale_factor: f64, // per-monitor DPI scale (96 DPI = 1.0)
+    pub x: i32,            // virtual-desktop position (window: top-left of its screen rect)
+    pub y: i32,
+    pub refresh_hz: u32,   // monitor refresh rate (0 for windows)
+}
+
+// ─── Cross-platform video frames ─────────────────────────────────────────
+//
+// Shared between the Windows WGC backend (`platform::CaptureHandler`) and
+// the Linux portal/PipeWire backend (`linux_capture`) so the frontend sees
+// the same `capture-frame` event shape regardless of which one is driving
+// a given OS.
+
+#[derive(Serialize, Clone)]
+pub struct FramePayload {
+    pub data: String, // base64 JPEG
+    pub width: u32,
+    pub height: u32,
+    pub timestamp: f64,    // ms since epoch
+    pub scale_factor: f64, // lets the frontend map physical px back to CSS px
+}
+
+// ─── Cross-platform audio loopback ──────────────────────────────────────
+//
+// Shared between the Windows WASAPI backend (`platform::WasapiLoopback`)
+// and the portable cpal backend (`stub::CpalLoopback`) so both feed the
+// frontend the same event, regardless of which one `start_capture` ends up
+// driving on a given OS.
+
+#[derive(Serialize, Clone)]
+pub struct AudioPayload {
+    pub data: Vec<f32>,   // interleaved PCM samples
+    pub sample_rate: u32, // e.g. 48000
+    pub channels: u16,    // e.g. 2
+    pub frames: u32,      // number of audio frames
+}
+
+/// One audio endpoint, surfaced by `enumerate_audio_devices` so the
+/// frontend can offer a picker the same way it does for `CaptureTarget`.
+#[derive(Serialize, Clone, Debug)]
+pub struct AudioDevice {
+    pub id: String,
+    pub name: String,
+    pub direction: String, // "render" | "capture"
+    pub is_default: bool,
+}
+
+/// A requested (or, from `supported_capture_formats`, an available) audio
+/// format: sample rate, channel count, and sample representation. Backends
+/// pick the nearest match rather than requiring an exact one, since not
+/// every device supports every combination.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct CaptureFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String, // "i16" | "f32"
+}
+
+impl Default for CaptureFormat {
+    fn default() -> Self {
+        Self {
+            sample_rate: 48_000,
+            channels: 2,
+            sample_format: "i16".to_string(),
+        }
+    }
+}
+
+/// A system-audio capture backend that decodes samples and emits
+/// `capture-audio` events until `stop_flag` is set (checked between
+/// buffers/callbacks, so shutdown is best-effort rather than instant).
+pub(crate) trait LoopbackSource {
+    fn start(
+        &self,
+        app: tauri::AppHandle,
+        stop_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    ) -> Result<(), String>;
+}
+
+/// Decode raw interleaved PCM bytes to f32 samples.
+pub(crate) fn decode_samples(raw: &[u8], bytes_per_sample: usize, total_samples: usize) -> Vec<f32> {
+    let mut samples = Vec::with_capacity(total_samples);
+    for i in 0..total_samples {
+        let offset = i * bytes_per_sample;
+        if offset + bytes_per_sample > raw.len() {
+            break;
+        }
+        let sample = if bytes_per_sample == 4 {
+            f32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]])
+        } else if bytes_per_sample == 2 {
+            i16::from_le_bytes([raw[offset], raw[offset + 1]]) as f32 / 32768.0
+        } else {
+            0.0
+        };
+        samples.push(sample);
+    }
+    samples
+}
+
+/// Downmix multi-channel audio to stereo (interleaved).
+pub(crate) fn downmix_to_stereo(all_samples: &[f32], channels: usize, frame_count: usize) -> Vec<f32> {
+    if channels == 2 {
+        all_samples.to_vec()
+    } else if channels == 1 {
+        let mut s = Vec::with_capacity(frame_count * 2);
+        for i in 0..frame_count {
+            let v = all_samples.get(i).copied().unwrap_or(0.0);
+            s.push(v);
+            s.push(v);
+        }
+        s
+    } else {
+        // Multi-channel (5.1, 7.1, etc.) → stereo: take L (ch0) and R (ch1)
+        let mut s = Vec::with_capacity(frame_count * 2);
+        for f in 0..frame_count {
+            let base = f * channels;
+            let l = all_samples.get(base).copied().unwrap_or(0.0);
+            let r = all_samples.get(base + 1).copied().unwrap_or(0.0);
+            s.push(l);
+            s.push(r);
+        }
+        s
+    }
+}
+
+// ─── Microphone mixing ───────────────────────────────────────────────────
+//
+// The mic-input thread (Windows: a capture `IAudioClient` on `eCapture`;
+// elsewhere: a cpal input `Stream`) doesn't emit its own `capture-audio`
+// events — it stages resampled stereo samples here, and whichever loopback
+// backend is driving `start_capture` mixes them in right before it emits.
+// This keeps a single `capture-audio` stream for the frontend instead of
+// two independent ones that would need client-side sync.
+
+struct MicBuffer {
+    samples: std::collections::VecDeque<f32>, // interleaved stereo @ 48kHz
+    mic_gain: f32,
+    loopback_gain: f32,
+}
+
+static MIC_BUFFER: std::sync::Mutex<Option<MicBuffer>> = std::sync::Mutex::new(None);
+
+/// Start staging mixed audio: call once before spawning the mic thread.
+pub(crate) fn start_mic_mix(mic_gain: f32, loopback_gain: f32) {
+    *MIC_BUFFER.lock().unwrap() = Some(MicBuffer {
+        samples: std::collections::VecDeque::new(),
+        mic_gain,
+        loopback_gain,
+    });
+}
+
+/// Stop mixing: loopback payloads pass through unchanged again.
+pub(crate) fn stop_mic_mix() {
+    *MIC_BUFFER.lock().unwrap() = None;
+}
+
+/// Append a stereo chunk that the mic thread has already resampled to
+/// 48kHz onto the shared mix buffer (no resampling happens here).
+pub(crate) fn stage_mic_samples(stereo_48k: &[f32]) {
+    if let Some(mic) = MIC_BUFFER.lock().unwrap().as_mut() {
+        mic.samples.extend(stereo_48k.iter().copied());
+    }
+}
+
+/// Common mix rate: mic and loopback buffers are each resampled here
+/// before being summed, since their source devices rarely share a rate.
+const MIX_SAMPLE_RATE: u32 = 48_000;
+
+/// Linear-interpolation resampler for interleaved stereo audio. Good
+/// enough for mixing a commentary mic under the loopback track — not
+/// intended to be broadcast-quality.
+pub(crate) fn resample_stereo(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || input.len() < 2 {
+        return input.to_vec();
+    }
+
+    let frame_count_in = input.len() / 2;
+    let frame_count_out =
+        ((frame_count_in as u64 * to_rate as u64) / from_rate as u64).max(1) as usize;
+    let mut out = Vec::with_capacity(frame_count_out * 2);
+
+    for i in 0..frame_count_out {
+        let src_pos = i as f64 * from_rate as f64 / to_rate as f64;
+        let idx = src_pos.floor() as usize;
+        let frac = (src_pos - idx as f64) as f32;
+        let idx_next = (idx + 1).min(frame_count_in - 1);
+
+        for ch in 0..2 {
+            let a = input.get(idx * 2 + ch).copied().unwrap_or(0.0);
+            let b = input.get(idx_next * 2 + ch).copied().unwrap_or(0.0);
+            out.push(a + (b - a) * frac);
+        }
+    }
+    out
+}
+
+/// Saturating soft-clip so `loopback + mic` summing can't produce a harsh
+/// digital-clipping pop when both sources are hot.
+fn soft_clip(sample: f32) -> f32 {
+    sample.tanh()
+}
+
+/// Resample `loopback` to the common mix rate and sum any staged mic
+/// samples into it with each source's configured gain, soft-clipping the
+/// result. Returns the (possibly resampled) audio and the rate it's now
+/// at. A no-op — `loopback` returned unchanged at `loopback_rate` — when
+/// mic capture isn't running, so non-mic callers pay nothing extra.
+pub(crate) fn mix_in_mic(loopback: &[f32], loopback_rate: u32) -> (Vec<f32>, u32) {
+    let mut guard = MIC_BUFFER.lock().unwrap();
+    let Some(mic) = guard.as_mut() else {
+        return (loopback.to_vec(), loopback_rate);
+    };
+
+    let loopback_48k = resample_stereo(loopback, loopback_rate, MIX_SAMPLE_RATE);
+
+    let mut mixed = Vec::with_capacity(loopback_48k.len());
+    for sample in loopback_48k {
+        let l = sample * mic.loopback_gain;
+        let m = mic.samples.pop_front().unwrap_or(0.0) * mic.mic_gain;
+        mixed.push(soft_clip(l + m));
+    }
+    (mixed, MIX_SAMPLE_RATE)
+}
+
+// ─── Native file dialogs ──────────────────────────────────────────────────
+//
+// `rfd` gives every platform its own native dialog through one API (GTK3's
+// portal-backed chooser on Linux, the system save/open panel on
+// Windows/macOS) — the same "one crate, native per OS" shape NFD offers,
+// just with an async entry point that fits this module's `async fn`
+// commands instead of a blocking call. Neither dialog is platform-specific
+// itself, so unlike video/audio capture these live here unconditionally
+// rather than split across `platform`/`stub`.
+//
+// There's no `output_path` on `start_capture` to feed these into — that's
+// a live-preview command with nowhere to write a file. The actual output
+// sink in this crate is `start_recording`'s `output_path` parameter, so
+// the frontend is expected to pass the path `pick_recording_destination`
+// returns straight into that.
+
+/// Open a native "Save As" dialog pre-filled with `default_name`, filtered
+/// to `format` (`"mp4" | "mkv" | "webm"`). `Ok(None)` means the user
+/// cancelled — a normal outcome, not an error the frontend needs to
+/// surface.
+#[tauri::command]
+pub async fn pick_recording_destination(
+    default_name: String,
+    format: String,
+) -> Result<Option<String>, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Save Recording")
+        .set_file_name(&default_name)
+        .add_filter(&format.to_uppercase(), &[format.as_str()])
+        .save_file()
+        .await;
+
+    Ok(handle.map(|f| f.path().to_string_lossy().into_owned()))
+}
+
+/// Open a native "Open" dialog for picking an existing recording to
+/// import. `Ok(None)` means the user cancelled.
+#[tauri::command]
+pub async fn pick_capture_import() -> Result<Option<String>, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Import Recording")
+        .add_filter("Video", &["mp4", "mkv", "webm"])
+        .pick_file()
+        .await;
+
+    Ok(handle.map(|f| f.path().to_string_lossy().into_owned()))
+}
+
+// ─── Capture capabilities ────────────────────────────────────────────────
+//
+// Today the only way the frontend can tell whether capture is supported at
+// all is to call `start_capture` and pattern-match the English error
+// string it gets back — slow, and racy if a user mashes the record button
+// before the first call resolves. `get_capture_capabilities` answers that
+// up front so the UI can disable the record button/audio toggle on sight
+// instead of discovering "unsupported" mid-action.
+#[derive(Serialize, Clone)]
+pub struct CaptureCapabilities {
+    pub supported: bool,
+    pub backend: &'static str, // "windows-gc" | "pipewire" | "screencapturekit" | "none"
+    pub supports_audio: bool,
+    pub supports_window_capture: bool,
+    pub supports_target_switch: bool,
+}
+
+#[tauri::command]
+pub async fn get_capture_capabilities() -> CaptureCapabilities {
+    #[cfg(target_os = "windows")]
+    {
+        CaptureCapabilities {
+            supported: true,
+            backend: "windows-gc",
+            supports_audio: true,
+            supports_window_capture: true,
+            supports_target_switch: true,
+        }
+    }
+    #[cfg(target_os = "linux")]
+    {
+        CaptureCapabilities {
+            supported: true,
+            backend: "pipewire",
+            supports_audio: true,
+            supports_window_capture: true,
+            supports_target_switch: true,
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        CaptureCapabilities {
+            supported: true,
+            backend: "screencapturekit",
+            supports_audio: true,
+            supports_window_capture: true,
+            supports_target_switch: true,
+        }
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    {
+        CaptureCapabilities {
+            supported: false,
+            backend: "none",
+            supports_audio: false,
+            supports_window_capture: false,
+            supports_target_switch: false,
+        }
+    }
 }
 
 // ─── Windows implementation ─────────────────────────────────────────────
@@ -66,6 +397,22 @@ mod platform {
     static AUDIO_STOP_FLAG: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
     static AUDIO_THREAD_HANDLE: Mutex<Option<std::thread::JoinHandle<()>>> = Mutex::new(None);
 
+    // ─── Recording state ─────────────────────────────────────────────
+    static RECORDING_TX: Mutex<Option<std::sync::mpsc::SyncSender<RecordedFrame>>> =
+        Mutex::new(None);
+    static RECORDING_THREAD_HANDLE: Mutex<Option<std::thread::JoinHandle<Result<(), String>>>> =
+        Mutex::new(None);
+
+    /// Most recently captured frame, kept around so `snapshot`/
+    /// `snapshot_to_clipboard` can grab a still without a dedicated
+    /// one-shot capture path.
+    struct LastFrame {
+        bgra: Vec<u8>,
+        width: u32,
+        height: u32,
+    }
+    static LAST_FRAME: Mutex<Option<LastFrame>> = Mutex::new(None);
+
     /// Type-erased wrapper so we can store CaptureControl in a static.
     trait CaptureControlHandle: Send + Sync {
         fn stop_capture(&self) -> Result<(), String>;
@@ -92,34 +439,29 @@ mod platform {
         }
     }
 
-    // ─── Frame event payloads ───────────────────────────────────────
-    #[derive(Serialize, Clone)]
-    pub struct FramePayload {
-        pub data: String, // base64 JPEG
-        pub width: u32,
-        pub height: u32,
-        pub timestamp: f64, // ms since epoch
-    }
-
-    #[derive(Serialize, Clone)]
-    pub struct AudioPayload {
-        pub data: Vec<f32>,   // interleaved PCM samples
-        pub sample_rate: u32, // e.g. 48000
-        pub channels: u16,    // e.g. 2
-        pub frames: u32,      // number of audio frames
-    }
+    use super::{
+        decode_samples, downmix_to_stereo, AudioDevice, AudioPayload, CaptureFormat, FramePayload,
+        LoopbackSource,
+    };
 
     // ─── WGC capture handler ────────────────────────────────────────
     struct CaptureHandler {
         app: AppHandle,
         fps_interval_ms: u64,
         last_frame_time: Instant,
+        scale_factor: f64,
+        /// Physical-pixel (x, y, w, h) sub-rectangle to crop each frame to,
+        /// set by `start_region_capture`. `None` for a normal full window/
+        /// monitor capture.
+        crop_region: Option<(u32, u32, u32, u32)>,
     }
 
     /// Flags passed through Settings → Context to the handler's `new()`.
     struct CaptureFlags {
         app: AppHandle,
         fps: u32,
+        scale_factor: f64,
+        crop_region: Option<(u32, u32, u32, u32)>,
     }
 
     impl GraphicsCaptureApiHandler for CaptureHandler {
@@ -132,6 +474,8 @@ mod platform {
                 app: ctx.flags.app,
                 fps_interval_ms: 1000 / fps as u64,
                 last_frame_time: Instant::now(),
+                scale_factor: ctx.flags.scale_factor,
+                crop_region: ctx.flags.crop_region,
             })
         }
 
@@ -148,23 +492,78 @@ mod platform {
             }
             self.last_frame_time = now;
 
-            let width = frame.width();
-            let height = frame.height();
+            let full_width = frame.width();
+            let full_height = frame.height();
 
             // Get frame buffer
             let mut buffer = frame.buffer()?;
-            let raw = buffer.as_raw_buffer();
+            let full_raw = buffer.as_raw_buffer();
+
+            let timestamp_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64()
+                * 1000.0;
 
-            // Convert BGRA → RGB for turbojpeg.
             // WGC frame buffers may have row padding (stride > width * 4),
             // especially for window captures. Compute actual stride from buffer size.
-            let expected_row_bytes = width as usize * 4;
-            let stride = if height > 0 {
-                raw.len() / height as usize
+            let expected_row_bytes = full_width as usize * 4;
+            let full_stride = if full_height > 0 {
+                full_raw.len() / full_height as usize
             } else {
                 expected_row_bytes
             };
 
+            // For `start_region_capture` sessions, crop to the requested
+            // sub-rectangle (clamped to the frame bounds) before anything
+            // downstream sees the frame, so recording/snapshot/preview all
+            // agree on what "the frame" is. Either way, de-stride into an
+            // owned tightly-packed buffer here — `full_raw` itself may have
+            // row padding (common for window captures), and downstream
+            // consumers (the recording tee, `LAST_FRAME`) tag the buffer
+            // with `width`/`height` alone, so a padded buffer silently
+            // desyncs them.
+            let (width, height, cx, cy) = match self.crop_region {
+                Some((cx, cy, cw, ch)) => {
+                    let cx = cx.min(full_width.saturating_sub(1));
+                    let cy = cy.min(full_height.saturating_sub(1));
+                    let cw = cw.min(full_width - cx).max(1);
+                    let ch = ch.min(full_height - cy).max(1);
+                    (cw, ch, cx, cy)
+                }
+                None => (full_width, full_height, 0, 0),
+            };
+            let stride = width as usize * 4;
+            let mut packed = Vec::with_capacity(width as usize * height as usize * 4);
+            for y in cy..cy + height {
+                let row_start = y as usize * full_stride + cx as usize * 4;
+                let row_end = row_start + stride;
+                if row_end <= full_raw.len() {
+                    packed.extend_from_slice(&full_raw[row_start..row_end]);
+                }
+            }
+            let raw = packed;
+
+            // Tee the raw BGRA frame to the recording encoder, if one is
+            // attached. Use try_send so a slow/stalled encoder never blocks
+            // the live capture path — we'd rather drop a frame from the
+            // recording than stutter the preview.
+            if let Some(tx) = RECORDING_TX.lock().unwrap().as_ref() {
+                let _ = tx.try_send(RecordedFrame {
+                    data: raw.to_vec(),
+                    width,
+                    height,
+                    timestamp_ms,
+                });
+            }
+
+            *LAST_FRAME.lock().unwrap() = Some(LastFrame {
+                bgra: raw.to_vec(),
+                width,
+                height,
+            });
+
+            // Convert BGRA → RGB for turbojpeg.
             let pixel_count = (width * height) as usize;
             let mut rgb = Vec::with_capacity(pixel_count * 3);
             for y in 0..height as usize {
@@ -193,11 +592,8 @@ mod platform {
                 data: base64::engine::general_purpose::STANDARD.encode(&*jpeg_data),
                 width,
                 height,
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs_f64()
-                    * 1000.0,
+                timestamp: timestamp_ms,
+                scale_factor: self.scale_factor,
             };
             let _ = self.app.emit("capture-frame", &payload);
 
@@ -206,11 +602,153 @@ mod platform {
 
         fn on_closed(&mut self) -> Result<(), Self::Error> {
             CAPTURE_RUNNING.store(false, Ordering::SeqCst);
+            // WGC tore itself down on its own (the captured window closed),
+            // so there's no guarantee the frontend ever calls `stop_capture`
+            // in response — it just sees `capture-stopped`. Stop the
+            // dedicated window-watch thread/hook here too, otherwise the
+            // next `start_capture` calls `start_window_watch` again, which
+            // unconditionally overwrites `WINDOW_WATCH` and orphans this
+            // one for the rest of the process's life.
+            stop_window_watch();
             let _ = self.app.emit("capture-stopped", ());
             Ok(())
         }
     }
 
+    // ─── DPI awareness ────────────────────────────────────────────────
+    //
+    // Without per-monitor awareness, Win32 virtualizes coordinates for
+    // non-DPI-aware processes: GetWindowRect/GetDeviceCaps report logical
+    // pixels scaled to the system DPI, so StretchBlt grabs the wrong
+    // region (or a blurry upscaled one) on HiDPI/mixed-DPI setups.
+
+    /// Opt the process into per-monitor-v2 DPI awareness. Call once at
+    /// startup, before any capture or thumbnail work — mirrors how winit
+    /// sets this for its own window handling.
+    pub fn init_dpi_awareness() {
+        use windows::Win32::UI::HiDpi::{
+            SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        };
+        unsafe {
+            let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        }
+    }
+
+    /// Scale factor (96 DPI = 1.0) for a specific window, preferring
+    /// `GetDpiForWindow` since it accounts for per-window overrides.
+    fn window_scale_factor(hwnd_val: isize) -> f64 {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::HiDpi::GetDpiForWindow;
+        unsafe {
+            let hwnd = HWND(hwnd_val as *mut std::ffi::c_void);
+            let dpi = GetDpiForWindow(hwnd);
+            if dpi == 0 {
+                1.0
+            } else {
+                dpi as f64 / 96.0
+            }
+        }
+    }
+
+    /// Top-left corner of a window in physical screen coordinates.
+    fn window_position(hwnd_val: isize) -> (i32, i32) {
+        use windows::Win32::Foundation::{HWND, RECT};
+        use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+        unsafe {
+            let hwnd = HWND(hwnd_val as *mut std::ffi::c_void);
+            let mut rect = RECT::default();
+            if GetWindowRect(hwnd, &mut rect).is_ok() {
+                (rect.left, rect.top)
+            } else {
+                (0, 0)
+            }
+        }
+    }
+
+    /// Virtual-desktop position and current refresh rate for a monitor,
+    /// via `EnumDisplaySettingsExW(ENUM_CURRENT_SETTINGS)`.
+    fn monitor_position_and_refresh(device_name: &str) -> (i32, i32, u32) {
+        use windows::Win32::Graphics::Gdi::{
+            EnumDisplaySettingsExW, DEVMODEW, ENUM_CURRENT_SETTINGS,
+        };
+        use windows::core::PCWSTR;
+
+        unsafe {
+            let device_wide: Vec<u16> =
+                device_name.encode_utf16().chain(std::iter::once(0)).collect();
+            let mut devmode: DEVMODEW = std::mem::zeroed();
+            devmode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+
+            let ok = EnumDisplaySettingsExW(
+                PCWSTR(device_wide.as_ptr()),
+                ENUM_CURRENT_SETTINGS,
+                &mut devmode,
+                0,
+            )
+            .as_bool();
+
+            if !ok {
+                return (0, 0, 0);
+            }
+
+            // dmPosition is only valid when DM_POSITION is set in dmFields,
+            // which it is for ENUM_CURRENT_SETTINGS on a real display.
+            let pos = devmode.Anonymous1.Anonymous2.dmPosition;
+            (pos.x, pos.y, devmode.dmDisplayFrequency)
+        }
+    }
+
+    /// Scale factor (96 DPI = 1.0) for the monitor with the given GDI
+    /// device name (e.g. `\\.\DISPLAY1`), via `EnumDisplayMonitors` +
+    /// `GetDpiForMonitor(MDT_EFFECTIVE_DPI)`.
+    fn monitor_scale_factor(device_name: &str) -> f64 {
+        use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+        use windows::Win32::Graphics::Gdi::{
+            EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+        };
+        use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+
+        struct Ctx<'a> {
+            target: &'a str,
+            scale: f64,
+        }
+
+        unsafe extern "system" fn callback(
+            hmon: HMONITOR,
+            _hdc: HDC,
+            _rect: *mut RECT,
+            lparam: LPARAM,
+        ) -> BOOL {
+            let ctx = &mut *(lparam.0 as *mut Ctx);
+            let mut info = MONITORINFOEXW::default();
+            info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+            if GetMonitorInfoW(hmon, &mut info as *mut _ as *mut _).as_bool() {
+                let name = String::from_utf16_lossy(&info.szDevice)
+                    .trim_end_matches('\0')
+                    .to_string();
+                if name == ctx.target {
+                    let mut dpi_x: u32 = 96;
+                    let mut dpi_y: u32 = 96;
+                    if GetDpiForMonitor(hmon, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_ok() {
+                        ctx.scale = dpi_x as f64 / 96.0;
+                    }
+                }
+            }
+            BOOL::from(true)
+        }
+
+        let mut ctx = Ctx { target: device_name, scale: 1.0 };
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                HDC::default(),
+                None,
+                Some(callback),
+                LPARAM(&mut ctx as *mut _ as isize),
+            );
+        }
+        ctx.scale
+    }
+
     // ─── Window visibility filter ────────────────────────────────────
     //
     // Filters out invisible / background windows using Win32 APIs:
@@ -353,6 +891,11 @@ mod platform {
     }
 
     /// Capture a window thumbnail by StretchBlt from the desktop DC at the window's screen rect.
+    ///
+    /// `GetWindowRect` only returns physical pixels because the process
+    /// opted into per-monitor DPI awareness via `init_dpi_awareness()` at
+    /// startup; without that, this would StretchBlt the wrong region on a
+    /// HiDPI/mixed-DPI setup.
     fn capture_window_thumbnail(hwnd_val: isize) -> (String, u32, u32) {
         use windows::Win32::Foundation::{HWND, RECT};
         use windows::Win32::Graphics::Gdi::*;
@@ -454,56 +997,222 @@ mod platform {
         }
     }
 
+    // ─── Owner-aware session filtering ──────────────────────────────
+    //
+    // Under Terminal Services / fast user switching, the same executable
+    // can run under several accounts at once, so matching a capture target
+    // by PID or process name alone can bind to (or enumerate) a window
+    // that belongs to a different user's session. This compares the
+    // candidate process's token owner SID against our own token's owner
+    // SID rather than session IDs directly, since that's what actually
+    // identifies "the same user", and it's cheap enough to call per
+    // enumerated window.
+
+    /// An owner SID copied out of a token info buffer, so it outlives the
+    /// buffer `GetTokenInformation` wrote it into.
+    struct OwnedSid(Vec<u8>);
+
+    fn token_owner_sid(token: windows::Win32::Foundation::HANDLE) -> Option<OwnedSid> {
+        use windows::Win32::Security::{
+            CopySid, GetLengthSid, GetTokenInformation, TokenOwner, PSID, TOKEN_OWNER,
+        };
+        unsafe {
+            let mut len = 0u32;
+            let _ = GetTokenInformation(token, TokenOwner, None, 0, &mut len);
+            if len == 0 {
+                return None;
+            }
+            let mut buf = vec![0u8; len as usize];
+            GetTokenInformation(
+                token,
+                TokenOwner,
+                Some(buf.as_mut_ptr() as *mut _),
+                len,
+                &mut len,
+            )
+            .ok()?;
+            let owner = &*(buf.as_ptr() as *const TOKEN_OWNER);
+            let sid_len = GetLengthSid(owner.Owner);
+            let mut sid_buf = vec![0u8; sid_len as usize];
+            CopySid(
+                sid_len,
+                PSID(sid_buf.as_mut_ptr() as *mut _),
+                owner.Owner,
+            )
+            .ok()?;
+            Some(OwnedSid(sid_buf))
+        }
+    }
+
+    fn process_owner_sid(pid: u32) -> Option<OwnedSid> {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::Security::TOKEN_QUERY;
+        use windows::Win32::System::Threading::{
+            OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut token = windows::Win32::Foundation::HANDLE::default();
+            let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token).is_ok();
+            let _ = CloseHandle(process);
+            if !opened {
+                return None;
+            }
+            let sid = token_owner_sid(token);
+            let _ = CloseHandle(token);
+            sid
+        }
+    }
+
+    fn current_owner_sid() -> Option<OwnedSid> {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::Security::TOKEN_QUERY;
+        use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+        unsafe {
+            let mut token = windows::Win32::Foundation::HANDLE::default();
+            OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).ok()?;
+            let sid = token_owner_sid(token);
+            let _ = CloseHandle(token);
+            sid
+        }
+    }
+
+    /// True if `pid` runs under the same token owner as this process —
+    /// i.e. the same interactive user. Fails *closed* (`false`) if either
+    /// SID can't be read: an unprivileged `OpenProcess`/`OpenProcessToken`
+    /// against another session's process is expected to be denied, and
+    /// that denial is exactly the signal this check exists to act on —
+    /// failing open here would wave through precisely the cross-session
+    /// targets it's supposed to block.
+    fn is_same_session_owner(pid: u32) -> bool {
+        match (process_owner_sid(pid), current_owner_sid()) {
+            (Some(a), Some(b)) => a.0 == b.0,
+            _ => false,
+        }
+    }
+
+    /// PID owning the window `target_id` names, if it's a `window:` target.
+    fn target_window_pid(target_id: &str) -> Option<u32> {
+        let hwnd_val: isize = target_id.strip_prefix("window:")?.parse().ok()?;
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+        let hwnd = HWND(hwnd_val as *mut std::ffi::c_void);
+        let mut pid: u32 = 0;
+        unsafe {
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        }
+        (pid != 0).then_some(pid)
+    }
+
+    /// Reject binding a capture to a window or process-loopback target that
+    /// belongs to another user's session. Checked again here (not just at
+    /// enumeration time) because a stale target id from an older picker
+    /// snapshot could otherwise slip past the enumeration-time filter.
+    fn validate_target_ownership(
+        target_id: &str,
+        target_process_id: u32,
+        restrict: bool,
+    ) -> Result<(), String> {
+        if !restrict {
+            return Ok(());
+        }
+        if let Some(pid) = target_window_pid(target_id) {
+            if !is_same_session_owner(pid) {
+                return Err("target window belongs to another user's session".into());
+            }
+        }
+        if target_process_id != 0 && !is_same_session_owner(target_process_id) {
+            return Err("target process belongs to another user's session".into());
+        }
+        Ok(())
+    }
+
+    /// Build a `CaptureTarget` for an already-enumerated window, applying
+    /// the same title/process/visibility filtering `enumerate_capture_targets`
+    /// always has. Returns `None` if the window shouldn't be shown in the
+    /// picker. Shared with `start_target_watch`, which only has an HWND to
+    /// go on and re-enumerates via `build_window_target_by_hwnd` below.
+    /// `restrict_to_current_session` drops windows owned by another user's
+    /// processes, same flag `enumerate_capture_targets`/`start_capture`
+    /// expose to the frontend.
+    fn build_window_target(win: &Window, restrict_to_current_session: bool) -> Option<CaptureTarget> {
+        let title = win.title().unwrap_or_default();
+        if title.is_empty() || title == "Program Manager" {
+            return None;
+        }
+
+        let process_name = win.process_name().unwrap_or_default();
+        if process_name.to_lowercase().contains("nexus") {
+            return None;
+        }
+
+        let hwnd_val = win.as_raw_hwnd() as isize;
+        if !is_capturable_window(hwnd_val, &process_name) {
+            return None;
+        }
+
+        let process_id = {
+            use windows::Win32::Foundation::HWND;
+            use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+            let hwnd = HWND(hwnd_val as *mut std::ffi::c_void);
+            let mut pid: u32 = 0;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)); }
+            pid
+        };
+
+        if restrict_to_current_session && process_id != 0 && !is_same_session_owner(process_id) {
+            return None;
+        }
+
+        let (thumbnail, width, height) = capture_window_thumbnail(hwnd_val);
+        let scale_factor = window_scale_factor(hwnd_val);
+        let (x, y) = window_position(hwnd_val);
+
+        Some(CaptureTarget {
+            id: format!("window:{}", hwnd_val),
+            title,
+            target_type: "window".to_string(),
+            process_name,
+            process_id,
+            width,
+            height,
+            thumbnail,
+            scale_factor,
+            x,
+            y,
+            refresh_hz: 0,
+        })
+    }
+
+    /// Same as `build_window_target`, but starting from just an HWND —
+    /// used by the target-watch hook, which only gets an HWND from its
+    /// WinEvent callback.
+    fn build_window_target_by_hwnd(hwnd_val: isize) -> Option<CaptureTarget> {
+        let windows = Window::enumerate().ok()?;
+        let win = windows
+            .into_iter()
+            .find(|w| w.as_raw_hwnd() as isize == hwnd_val)?;
+        // The watch hook always restricts to the current session, matching
+        // `enumerate_capture_targets`'s default.
+        build_window_target(&win, true)
+    }
+
     // ─── Enumerate targets ──────────────────────────────────────────
     #[tauri::command]
-    pub async fn enumerate_capture_targets() -> Result<Vec<CaptureTarget>, String> {
+    pub async fn enumerate_capture_targets(
+        restrict_to_current_session: Option<bool>,
+    ) -> Result<Vec<CaptureTarget>, String> {
+        let restrict = restrict_to_current_session.unwrap_or(true);
         // Run on a blocking thread because Win32 API calls are involved
-        tauri::async_runtime::spawn_blocking(|| {
+        tauri::async_runtime::spawn_blocking(move || {
             let mut targets = Vec::new();
 
             // Enumerate windows
             if let Ok(windows) = Window::enumerate() {
                 for win in windows {
-                    let title = win.title().unwrap_or_default();
-                    if title.is_empty() || title == "Program Manager" {
-                        continue;
-                    }
-
-                    let process_name = win.process_name().unwrap_or_default();
-
-                    // Skip our own window
-                    if process_name.to_lowercase().contains("nexus") {
-                        continue;
-                    }
-
-                    let hwnd_val = win.as_raw_hwnd() as isize;
-
-                    // Skip invisible / background windows
-                    if !is_capturable_window(hwnd_val, &process_name) {
-                        continue;
+                    if let Some(target) = build_window_target(&win, restrict) {
+                        targets.push(target);
                     }
-
-                    let process_id = {
-                        use windows::Win32::Foundation::HWND;
-                        use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
-                        let hwnd = HWND(hwnd_val as *mut std::ffi::c_void);
-                        let mut pid: u32 = 0;
-                        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)); }
-                        pid
-                    };
-
-                    let (thumbnail, width, height) = capture_window_thumbnail(hwnd_val);
-
-                    targets.push(CaptureTarget {
-                        id: format!("window:{}", hwnd_val),
-                        title,
-                        target_type: "window".to_string(),
-                        process_name,
-                        process_id,
-                        width,
-                        height,
-                        thumbnail,
-                    });
                 }
             }
 
@@ -516,6 +1225,8 @@ mod platform {
                     let w = mon.width().unwrap_or(0);
                     let h = mon.height().unwrap_or(0);
                     let thumbnail = capture_monitor_thumbnail(&name);
+                    let scale_factor = monitor_scale_factor(&name);
+                    let (x, y, refresh_hz) = monitor_position_and_refresh(&name);
 
                     targets.push(CaptureTarget {
                         id: format!("monitor:{}", i),
@@ -526,6 +1237,10 @@ mod platform {
                         width: w,
                         height: h,
                         thumbnail,
+                        scale_factor,
+                        x,
+                        y,
+                        refresh_hz,
                     });
                 }
             }
@@ -538,11 +1253,13 @@ mod platform {
 
     // ─── WGC capture helper ─────────────────────────────────────────
     /// Start a WGC capture session for the given target and return its control handle.
-    /// Shared by `start_capture` and `switch_capture_target`.
+    /// Shared by `start_capture`, `switch_capture_target`, and
+    /// `start_region_capture` (via `crop_region`).
     async fn start_wgc_capture(
         app: AppHandle,
         target_id: String,
         fps: u32,
+        crop_region: Option<(u32, u32, u32, u32)>,
     ) -> Result<Box<dyn CaptureControlHandle>, String> {
         let fps = fps.max(1).min(60);
 
@@ -557,9 +1274,28 @@ mod platform {
         let target_value = parts[1].to_string();
 
         tauri::async_runtime::spawn_blocking(move || -> Result<Box<dyn CaptureControlHandle>, String> {
+            let scale_factor = match target_type.as_str() {
+                "window" => target_value
+                    .parse::<isize>()
+                    .map(window_scale_factor)
+                    .unwrap_or(1.0),
+                "monitor" => target_value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| Monitor::enumerate().ok()?.into_iter().nth(index))
+                    .and_then(|mon| mon.device_name().ok())
+                    .map(|name| monitor_scale_factor(&name))
+                    .unwrap_or(1.0),
+                _ => 1.0,
+            };
+
+            let watch_app = capture_app.clone();
+
             let flags = CaptureFlags {
                 app: capture_app,
                 fps,
+                scale_factor,
+                crop_region,
             };
 
             // WithoutBorder requires Win11+ (IsBorderRequired API).
@@ -597,6 +1333,8 @@ mod platform {
                     let control = CaptureHandler::start_free_threaded(settings)
                         .map_err(|e| format!("start capture: {:?}", e))?;
 
+                    start_window_watch(watch_app, hwnd_val);
+
                     let wrapper: Box<dyn CaptureControlHandle> = Box::new(ControlWrapper {
                         inner: Mutex::new(Some(control)),
                     });
@@ -640,37 +1378,380 @@ mod platform {
         .map_err(|e| format!("spawn_blocking: {}", e))?
     }
 
-    // ─── Start capture ──────────────────────────────────────────────
-    #[tauri::command]
-    pub async fn start_capture(
-        app: AppHandle,
-        target_id: String,
-        fps: u32,
-        capture_audio: bool,
-        target_process_id: u32,
-    ) -> Result<(), String> {
-        if CAPTURE_RUNNING.load(Ordering::SeqCst) {
-            return Err("Capture already running".into());
-        }
-
-        let control = start_wgc_capture(app.clone(), target_id, fps).await?;
+    // ─── Window lifecycle watch (WinEvent hook) ───────────────────────
+    //
+    // WGC's `on_closed` only fires once the capture session itself tears
+    // down, so there's no signal for "the captured window moved" or "was
+    // minimized" while the session is still alive. `SetWinEventHook`
+    // fills that gap, but its callback receives no user-data pointer, so
+    // — like glutin's win32 event-loop thread — we run the hook on a
+    // dedicated thread and stash what the callback needs in a
+    // thread-local before pumping messages.
+
+    thread_local! {
+        static WATCH_CTX: std::cell::RefCell<Option<(isize, AppHandle)>> =
+            std::cell::RefCell::new(None);
+    }
 
-        CAPTURE_RUNNING.store(true, Ordering::SeqCst);
-        *CAPTURE_CONTROL.lock().unwrap() = Some(control);
+    struct WindowWatchHandle {
+        thread_id: u32,
+        join_handle: std::thread::JoinHandle<()>,
+    }
+    static WINDOW_WATCH: Mutex<Option<WindowWatchHandle>> = Mutex::new(None);
+
+    unsafe extern "system" fn win_event_proc(
+        _hook: windows::Win32::UI::Accessibility::HWINEVENTHOOK,
+        event: u32,
+        hwnd: windows::Win32::Foundation::HWND,
+        _id_object: i32,
+        _id_child: i32,
+        _event_thread: u32,
+        _event_time: u32,
+    ) {
+        use windows::Win32::Foundation::RECT;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetWindowRect, EVENT_OBJECT_DESTROY, EVENT_OBJECT_LOCATIONCHANGE,
+            EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART,
+        };
 
-        // Start WASAPI audio loopback if requested
-        if capture_audio {
-            let audio_app = app.clone();
-            let stop_flag = Arc::new(AtomicBool::new(false));
-            *AUDIO_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
+        WATCH_CTX.with(|ctx| {
+            let ctx = ctx.borrow();
+            let Some((target_hwnd, app)) = ctx.as_ref() else {
+                return;
+            };
+            if hwnd.0 as isize != *target_hwnd {
+                return;
+            }
 
-            let target_pid = target_process_id;
-            let handle = std::thread::spawn(move || {
-                if let Err(e) = run_wasapi_loopback(audio_app, stop_flag, target_pid) {
-                    eprintln!("WASAPI loopback error: {}", e);
+            match event {
+                EVENT_OBJECT_LOCATIONCHANGE => {
+                    let mut rect = RECT::default();
+                    if GetWindowRect(hwnd, &mut rect).is_ok() {
+                        let _ = app.emit(
+                            "capture-target-moved",
+                            serde_json::json!({
+                                "x": rect.left,
+                                "y": rect.top,
+                                "width": rect.right - rect.left,
+                                "height": rect.bottom - rect.top,
+                            }),
+                        );
+                    }
                 }
-            });
+                EVENT_SYSTEM_MINIMIZESTART => {
+                    let _ = app.emit("capture-target-minimized", true);
+                }
+                EVENT_SYSTEM_MINIMIZEEND => {
+                    let _ = app.emit("capture-target-minimized", false);
+                }
+                EVENT_OBJECT_DESTROY => {
+                    let _ = app.emit("capture-target-closed", ());
+                }
+                _ => {}
+            }
+        });
+    }
+
+    /// Spawn the WinEvent pump thread for `hwnd_val`. Only called for
+    /// window targets; monitor targets have no HWND to watch.
+    fn start_window_watch(app: AppHandle, hwnd_val: isize) {
+        use windows::Win32::System::Threading::GetCurrentThreadId;
+        use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, WINEVENT_OUTOFCONTEXT};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            DispatchMessageW, GetMessageW, TranslateMessage, EVENT_OBJECT_DESTROY,
+            EVENT_OBJECT_LOCATIONCHANGE, EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART, MSG,
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+
+        let join_handle = std::thread::spawn(move || {
+            WATCH_CTX.with(|ctx| *ctx.borrow_mut() = Some((hwnd_val, app)));
+
+            let thread_id = unsafe { GetCurrentThreadId() };
+            let _ = tx.send(thread_id);
+
+            let hooks: Vec<_> = [
+                (EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_LOCATIONCHANGE),
+                (EVENT_SYSTEM_MINIMIZESTART, EVENT_SYSTEM_MINIMIZESTART),
+                (EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZEEND),
+                (EVENT_OBJECT_DESTROY, EVENT_OBJECT_DESTROY),
+            ]
+            .iter()
+            .map(|&(min, max)| unsafe {
+                SetWinEventHook(min, max, None, Some(win_event_proc), 0, 0, WINEVENT_OUTOFCONTEXT)
+            })
+            .collect();
+
+            // Pump until `stop_window_watch` posts WM_QUIT to this thread.
+            let mut msg = MSG::default();
+            unsafe {
+                while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+
+                for hook in hooks {
+                    let _ = UnhookWinEvent(hook);
+                }
+            }
+
+            WATCH_CTX.with(|ctx| *ctx.borrow_mut() = None);
+        });
+
+        // Block briefly for the thread to report its id — needed so
+        // `stop_window_watch` can post WM_QUIT to the right message queue.
+        if let Ok(thread_id) = rx.recv_timeout(std::time::Duration::from_secs(1)) {
+            *WINDOW_WATCH.lock().unwrap() = Some(WindowWatchHandle {
+                thread_id,
+                join_handle,
+            });
+        }
+    }
+
+    fn stop_window_watch() {
+        use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+        if let Some(watch) = WINDOW_WATCH.lock().unwrap().take() {
+            unsafe {
+                let _ = PostThreadMessageW(watch.thread_id, WM_QUIT, windows::Win32::Foundation::WPARAM(0), windows::Win32::Foundation::LPARAM(0));
+            }
+            let _ = watch.join_handle.join();
+        }
+    }
+
+    // ─── Target list watch (system-wide WinEvent hook) ────────────────
+    //
+    // `enumerate_capture_targets` is a one-shot snapshot; the picker would
+    // otherwise have to re-poll it to notice a new window opening. The
+    // four object-lifecycle events we care about — CREATE, DESTROY, SHOW,
+    // HIDE — are a contiguous range (0x8000-0x8003), so one system-wide
+    // `SetWinEventHook` (hwnd = NULL) covers all of them, on the same
+    // dedicated-thread-plus-thread-local-stash pattern as the per-window
+    // watch above. Top-level windows generate a lot of incidental
+    // show/hide churn as child controls repaint, so events are coalesced
+    // per-HWND on the pump thread instead of emitted unconditionally.
+
+    thread_local! {
+        static TARGET_WATCH_CTX: std::cell::RefCell<Option<AppHandle>> =
+            std::cell::RefCell::new(None);
+        static TARGET_WATCH_DEBOUNCE: std::cell::RefCell<std::collections::HashMap<isize, std::time::Instant>> =
+            std::cell::RefCell::new(std::collections::HashMap::new());
+    }
+
+    const TARGET_WATCH_DEBOUNCE_WINDOW: std::time::Duration = std::time::Duration::from_millis(250);
+
+    struct TargetWatchHandle {
+        thread_id: u32,
+        join_handle: std::thread::JoinHandle<()>,
+    }
+    static TARGET_WATCH: Mutex<Option<TargetWatchHandle>> = Mutex::new(None);
+
+    /// Returns `false` if `hwnd_val` had an event within the debounce window,
+    /// so the caller should skip emitting; otherwise records the current
+    /// time for `hwnd_val` and returns `true`.
+    fn target_watch_should_emit(hwnd_val: isize) -> bool {
+        TARGET_WATCH_DEBOUNCE.with(|debounce| {
+            let mut debounce = debounce.borrow_mut();
+            let now = std::time::Instant::now();
+            match debounce.get(&hwnd_val) {
+                Some(last) if now.duration_since(*last) < TARGET_WATCH_DEBOUNCE_WINDOW => false,
+                _ => {
+                    debounce.insert(hwnd_val, now);
+                    true
+                }
+            }
+        })
+    }
+
+    unsafe extern "system" fn target_win_event_proc(
+        _hook: windows::Win32::UI::Accessibility::HWINEVENTHOOK,
+        event: u32,
+        hwnd: windows::Win32::Foundation::HWND,
+        id_object: i32,
+        _id_child: i32,
+        _event_thread: u32,
+        _event_time: u32,
+    ) {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE, EVENT_OBJECT_SHOW,
+            OBJID_WINDOW,
+        };
+
+        // Only top-level window objects — child-control events share the
+        // same event range and would otherwise dominate the debounce map.
+        if id_object != OBJID_WINDOW.0 {
+            return;
+        }
+        let hwnd_val = hwnd.0 as isize;
+        if hwnd_val == 0 || !target_watch_should_emit(hwnd_val) {
+            return;
+        }
+
+        TARGET_WATCH_CTX.with(|ctx| {
+            let ctx = ctx.borrow();
+            let Some(app) = ctx.as_ref() else { return };
+
+            match event {
+                EVENT_OBJECT_CREATE | EVENT_OBJECT_SHOW => {
+                    if let Some(target) = build_window_target_by_hwnd(hwnd_val) {
+                        let _ = app.emit("capture-target-added", target);
+                    }
+                }
+                EVENT_OBJECT_DESTROY | EVENT_OBJECT_HIDE => {
+                    let _ = app.emit(
+                        "capture-target-removed",
+                        format!("window:{}", hwnd_val),
+                    );
+                    TARGET_WATCH_DEBOUNCE.with(|debounce| {
+                        debounce.borrow_mut().remove(&hwnd_val);
+                    });
+                }
+                _ => {}
+            }
+        });
+    }
+
+    fn stop_target_watch_inner() {
+        use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+        if let Some(watch) = TARGET_WATCH.lock().unwrap().take() {
+            unsafe {
+                let _ = PostThreadMessageW(
+                    watch.thread_id,
+                    WM_QUIT,
+                    windows::Win32::Foundation::WPARAM(0),
+                    windows::Win32::Foundation::LPARAM(0),
+                );
+            }
+            let _ = watch.join_handle.join();
+        }
+    }
+
+    /// Start pushing `capture-target-added`/`capture-target-removed` events
+    /// as windows open and close, instead of requiring the frontend to
+    /// re-poll `enumerate_capture_targets`. Safe to call again while
+    /// already running — it restarts the hook.
+    #[tauri::command]
+    pub async fn start_target_watch(app: AppHandle) -> Result<(), String> {
+        use windows::Win32::System::Threading::GetCurrentThreadId;
+        use windows::Win32::UI::Accessibility::{
+            SetWinEventHook, UnhookWinEvent, WINEVENT_OUTOFCONTEXT,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{
+            DispatchMessageW, GetMessageW, TranslateMessage, EVENT_OBJECT_CREATE,
+            EVENT_OBJECT_HIDE, MSG,
+        };
+
+        stop_target_watch_inner();
+
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+
+        let join_handle = std::thread::spawn(move || {
+            TARGET_WATCH_CTX.with(|ctx| *ctx.borrow_mut() = Some(app));
+
+            let thread_id = unsafe { GetCurrentThreadId() };
+            let _ = tx.send(thread_id);
+
+            let hook = unsafe {
+                SetWinEventHook(
+                    EVENT_OBJECT_CREATE,
+                    EVENT_OBJECT_HIDE,
+                    None,
+                    Some(target_win_event_proc),
+                    0,
+                    0,
+                    WINEVENT_OUTOFCONTEXT,
+                )
+            };
+
+            // Pump until `stop_target_watch` posts WM_QUIT to this thread.
+            let mut msg = MSG::default();
+            unsafe {
+                while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+                let _ = UnhookWinEvent(hook);
+            }
+
+            TARGET_WATCH_CTX.with(|ctx| *ctx.borrow_mut() = None);
+            TARGET_WATCH_DEBOUNCE.with(|debounce| debounce.borrow_mut().clear());
+        });
+
+        // Block briefly for the thread to report its id — needed so
+        // `stop_target_watch` can post WM_QUIT to the right message queue.
+        if let Ok(thread_id) = rx.recv_timeout(std::time::Duration::from_secs(1)) {
+            *TARGET_WATCH.lock().unwrap() = Some(TargetWatchHandle {
+                thread_id,
+                join_handle,
+            });
+            Ok(())
+        } else {
+            Err("target watch thread failed to start".to_string())
+        }
+    }
+
+    #[tauri::command]
+    pub async fn stop_target_watch() -> Result<(), String> {
+        stop_target_watch_inner();
+        Ok(())
+    }
+
+    // ─── Start capture ──────────────────────────────────────────────
+    #[tauri::command]
+    pub async fn start_capture(
+        app: AppHandle,
+        target_id: String,
+        fps: u32,
+        capture_audio: bool,
+        target_process_id: u32,
+        audio_device_id: Option<String>,
+        capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+        restrict_to_current_session: Option<bool>,
+    ) -> Result<(), String> {
+        if CAPTURE_RUNNING.load(Ordering::SeqCst) {
+            return Err("Capture already running".into());
+        }
+        validate_target_ownership(
+            &target_id,
+            target_process_id,
+            restrict_to_current_session.unwrap_or(true),
+        )?;
+
+        let control = start_wgc_capture(app.clone(), target_id, fps, None).await?;
+
+        CAPTURE_RUNNING.store(true, Ordering::SeqCst);
+        *CAPTURE_CONTROL.lock().unwrap() = Some(control);
+        let _ = app.emit("capture-started", ());
+
+        // Start WASAPI audio loopback if requested
+        if capture_audio {
+            let audio_app = app.clone();
+            let stop_flag = Arc::new(AtomicBool::new(false));
+            *AUDIO_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
+
+            let source = WasapiLoopback {
+                target_pid: target_process_id,
+                device_id: audio_device_id,
+                format: capture_format.unwrap_or_default(),
+            };
+            let handle = std::thread::spawn(move || {
+                if let Err(e) = source.start(audio_app, stop_flag) {
+                    eprintln!("WASAPI loopback error: {}", e);
+                }
+            });
             *AUDIO_THREAD_HANDLE.lock().unwrap() = Some(handle);
+
+            // Mic-mixing only makes sense on top of a running loopback
+            // thread, since that's the thread that actually emits
+            // `capture-audio` — without it there's nothing to mix into.
+            if capture_mic {
+                start_mic_thread(app.clone(), mic_device_id, mic_gain, loopback_gain);
+            }
         }
 
         Ok(())
@@ -683,15 +1764,25 @@ mod platform {
         target_id: String,
         fps: u32,
         target_process_id: u32,
+        audio_device_id: Option<String>,
+        capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
     ) -> Result<(), String> {
         if !CAPTURE_RUNNING.load(Ordering::SeqCst) {
             return Err("No capture running".into());
         }
+        // Always session-restricted here (no opt-out param) — switching
+        // target must never silently grab another user's window.
+        validate_target_ownership(&target_id, target_process_id, true)?;
 
         // Stop current WGC capture
         if let Some(control) = CAPTURE_CONTROL.lock().unwrap().take() {
             control.stop_capture()?;
         }
+        stop_window_watch();
 
         // Restart WASAPI loopback with the new process's PID
         // (stop old audio thread, start new one)
@@ -701,23 +1792,64 @@ mod platform {
         if let Some(handle) = AUDIO_THREAD_HANDLE.lock().unwrap().take() {
             let _ = handle.join();
         }
+        stop_mic_thread();
 
         // Start new WASAPI loopback for the new target process
         let audio_app = app.clone();
         let stop_flag = Arc::new(AtomicBool::new(false));
         *AUDIO_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
 
-        let target_pid = target_process_id;
+        let source = WasapiLoopback {
+            target_pid: target_process_id,
+            device_id: audio_device_id,
+            format: capture_format.unwrap_or_default(),
+        };
         let handle = std::thread::spawn(move || {
-            if let Err(e) = run_wasapi_loopback(audio_app, stop_flag, target_pid) {
+            if let Err(e) = source.start(audio_app, stop_flag) {
                 eprintln!("WASAPI loopback error: {}", e);
             }
         });
         *AUDIO_THREAD_HANDLE.lock().unwrap() = Some(handle);
 
+        if capture_mic {
+            start_mic_thread(app.clone(), mic_device_id, mic_gain, loopback_gain);
+        }
+
         // Start a new WGC capture for the new target
-        let control = start_wgc_capture(app, target_id, fps).await?;
+        let control = start_wgc_capture(app, target_id, fps, None).await?;
+        *CAPTURE_CONTROL.lock().unwrap() = Some(control);
+
+        Ok(())
+    }
+
+    // ─── Region capture ──────────────────────────────────────────────
+    //
+    // Crops a monitor capture to a sub-rectangle in `CaptureHandler::
+    // on_frame_arrived` rather than spawning a second full-monitor WGC
+    // session, so picture-in-picture / sub-region streaming doesn't double
+    // the GPU capture cost.
+    #[tauri::command]
+    pub async fn start_region_capture(
+        app: AppHandle,
+        target_id: String,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+        fps: u32,
+    ) -> Result<(), String> {
+        if CAPTURE_RUNNING.load(Ordering::SeqCst) {
+            return Err("Capture already running".into());
+        }
+        if !target_id.starts_with("monitor:") {
+            return Err("Region capture only supports monitor targets".into());
+        }
+
+        let control = start_wgc_capture(app.clone(), target_id, fps, Some((x, y, w, h))).await?;
+
+        CAPTURE_RUNNING.store(true, Ordering::SeqCst);
         *CAPTURE_CONTROL.lock().unwrap() = Some(control);
+        let _ = app.emit("capture-started", ());
 
         Ok(())
     }
@@ -739,406 +1871,626 @@ mod platform {
             let _ = handle.join();
         }
 
+        // Same COM-cleanup care applies to the mic thread, if one is running.
+        stop_mic_thread();
+
         // Stop WGC capture
         if let Some(control) = CAPTURE_CONTROL.lock().unwrap().take() {
             control.stop_capture()?;
         }
+        stop_window_watch();
 
         Ok(())
     }
 
-    // ─── Shared audio helpers ────────────────────────────────────────
-
-    /// Decode raw bytes to f32 samples.
-    fn decode_samples(raw: &[u8], bytes_per_sample: usize, total_samples: usize) -> Vec<f32> {
-        let mut samples = Vec::with_capacity(total_samples);
-        for i in 0..total_samples {
-            let offset = i * bytes_per_sample;
-            if offset + bytes_per_sample > raw.len() {
-                break;
-            }
-            let sample = if bytes_per_sample == 4 {
-                f32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]])
-            } else if bytes_per_sample == 2 {
-                i16::from_le_bytes([raw[offset], raw[offset + 1]]) as f32 / 32768.0
-            } else {
-                0.0
-            };
-            samples.push(sample);
+    /// Start system-audio loopback on its own, independent of video
+    /// capture — useful when the caller only wants audio, and gives this
+    /// command the same shape as the portable cpal version on other
+    /// platforms. `target_pid == 0` captures full-system audio.
+    #[tauri::command]
+    pub async fn start_audio_capture(
+        app: AppHandle,
+        target_process_id: u32,
+        audio_device_id: Option<String>,
+        capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+    ) -> Result<(), String> {
+        if AUDIO_THREAD_HANDLE.lock().unwrap().is_some() {
+            return Err("Audio capture already running".into());
         }
-        samples
-    }
 
-    /// Downmix multi-channel audio to stereo (interleaved).
-    fn downmix_to_stereo(all_samples: &[f32], channels: usize, frame_count: usize) -> Vec<f32> {
-        if channels == 2 {
-            all_samples.to_vec()
-        } else if channels == 1 {
-            let mut s = Vec::with_capacity(frame_count * 2);
-            for i in 0..frame_count {
-                let v = all_samples.get(i).copied().unwrap_or(0.0);
-                s.push(v);
-                s.push(v);
-            }
-            s
-        } else {
-            // Multi-channel (5.1, 7.1, etc.) → stereo: take L (ch0) and R (ch1)
-            let mut s = Vec::with_capacity(frame_count * 2);
-            for f in 0..frame_count {
-                let base = f * channels;
-                let l = all_samples.get(base).copied().unwrap_or(0.0);
-                let r = all_samples.get(base + 1).copied().unwrap_or(0.0);
-                s.push(l);
-                s.push(r);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        *AUDIO_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
+
+        let source = WasapiLoopback {
+            target_pid: target_process_id,
+            device_id: audio_device_id,
+            format: capture_format.unwrap_or_default(),
+        };
+        let audio_app = app.clone();
+        let handle = std::thread::spawn(move || {
+            if let Err(e) = source.start(audio_app, stop_flag) {
+                eprintln!("WASAPI loopback error: {}", e);
             }
-            s
+        });
+        *AUDIO_THREAD_HANDLE.lock().unwrap() = Some(handle);
+
+        if capture_mic {
+            start_mic_thread(app, mic_device_id, mic_gain, loopback_gain);
         }
-    }
 
-    // ─── WASAPI loopback capture (entry point) ───────────────────────
+        Ok(())
+    }
 
-    /// Try process loopback first (Win10 2004+), fall back to regular.
-    /// target_pid > 0: INCLUDE mode (window share — capture only that app's audio)
-    /// target_pid == 0: EXCLUDE mode (monitor share — all system audio minus Nexus)
-    fn run_wasapi_loopback(app: AppHandle, stop_flag: Arc<AtomicBool>, target_pid: u32) -> Result<(), String> {
-        match run_process_loopback(&app, &stop_flag, target_pid) {
-            Ok(()) => return Ok(()),
-            Err(e) => {
-                println!(
-                    "[WASAPI] Process loopback unavailable: {}. \
-                     Falling back to regular loopback (voice echo possible).",
-                    e
-                );
-                let _ = app.emit(
-                    "wasapi-info",
-                    "WASAPI: regular loopback (process loopback unavailable)",
-                );
-            }
+    #[tauri::command]
+    pub async fn stop_audio_capture() -> Result<(), String> {
+        if let Some(flag) = AUDIO_STOP_FLAG.lock().unwrap().take() {
+            flag.store(true, Ordering::SeqCst);
+        }
+        if let Some(handle) = AUDIO_THREAD_HANDLE.lock().unwrap().take() {
+            let _ = handle.join();
         }
-        run_regular_loopback(app, stop_flag)
+        stop_mic_thread();
+        Ok(())
     }
 
-    // ─── Process loopback (Windows 10 2004+) ──────────────────────────
+    // ─── Recording (capture → encoder) ────────────────────────────────
     //
-    // target_pid > 0: INCLUDE mode — capture only the target process tree's audio
-    //                 (window share: "share only the selected app's audio")
-    // target_pid == 0: EXCLUDE mode — capture all system audio EXCEPT our own process
-    //                  (monitor share: "share all system audio minus Nexus")
-
-    /// Raw PROPVARIANT layout for VT_BLOB on x64.
-    /// Used to pass AUDIOCLIENT_ACTIVATION_PARAMS to ActivateAudioInterfaceAsync.
-    #[repr(C)]
-    struct PropVariantBlob {
-        vt: u16,             // VT_BLOB = 0x0041
-        reserved1: u16,
-        reserved2: u16,
-        reserved3: u16,
-        cb_size: u32,        // BLOB.cbSize
-        _pad: u32,           // alignment padding on x64
-        p_blob_data: *const u8, // BLOB.pBlobData
+    // Recording tees the frame stream already flowing through
+    // `CaptureHandler::on_frame_arrived` into an ffmpeg child process over
+    // a bounded channel. We do not restart or otherwise touch the WGC
+    // session — a recording is just another consumer of the same frames
+    // that `start_capture` is already producing.
+
+    /// One raw frame queued for the encoder thread.
+    struct RecordedFrame {
+        data: Vec<u8>, // BGRA, as delivered by WGC
+        width: u32,
+        height: u32,
+        timestamp_ms: f64,
     }
 
-    /// Activation params for process loopback.
-    #[repr(C)]
-    struct ProcessLoopbackActivationParams {
-        activation_type: i32,        // AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK = 1
-        target_process_id: u32,      // target PID (INCLUDE) or our PID (EXCLUDE)
-        process_loopback_mode: i32,  // 0 = INCLUDE_TARGET_PROCESS_TREE, 1 = EXCLUDE_TARGET_PROCESS_TREE
+    /// Options controlling the ffmpeg encode, supplied by the frontend.
+    #[derive(serde::Deserialize, Clone)]
+    pub struct RecordingOptions {
+        pub codec: String,        // "h264" | "vp9" | "av1"
+        pub bitrate_kbps: u32,
+        pub container: String,    // "mp4" | "webm"
     }
 
-    /// COM completion handler for ActivateAudioInterfaceAsync.
-    #[windows::core::implement(IActivateAudioInterfaceCompletionHandler)]
-    struct ActivateCompletionHandler {
-        event: isize, // HANDLE as isize (Send-safe)
+    #[derive(Serialize, Clone)]
+    struct RecordingProgress {
+        elapsed_ms: f64,
+        bytes_written: u64,
     }
 
-    impl IActivateAudioInterfaceCompletionHandler_Impl for ActivateCompletionHandler_Impl {
-        fn ActivateCompleted(
-            &self,
-            _operation: Option<&IActivateAudioInterfaceAsyncOperation>,
-        ) -> windows::core::Result<()> {
-            unsafe {
-                let _ = SetEvent(HANDLE(self.event as *mut std::ffi::c_void));
+    impl RecordingOptions {
+        fn ffmpeg_codec_name(&self) -> &str {
+            match self.codec.as_str() {
+                "vp9" => "libvpx-vp9",
+                "av1" => "libaom-av1",
+                _ => "libx264",
             }
-            Ok(())
         }
     }
 
-    fn run_process_loopback(
-        app: &AppHandle,
-        stop_flag: &Arc<AtomicBool>,
-        target_pid: u32,
+    /// Run the ffmpeg child process that receives raw BGRA frames on `rx`
+    /// and encodes them to `output_path`. Frames arrive at irregular
+    /// intervals (WGC only delivers on change, and `on_frame_arrived` also
+    /// throttles to the requested fps), so we stamp `-use_wallclock_as_timestamps`
+    /// rather than assuming a fixed fps — ffmpeg derives presentation
+    /// timestamps from wall-clock arrival time instead of a frame counter.
+    fn run_recording_encoder(
+        app: AppHandle,
+        rx: std::sync::mpsc::Receiver<RecordedFrame>,
+        output_path: String,
+        options: RecordingOptions,
     ) -> Result<(), String> {
-        use windows::core::Interface;
-        use windows::Win32::Media::Audio::*;
-        use windows::Win32::System::Com::*;
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let first = rx.recv().map_err(|_| "Recording channel closed before any frame".to_string())?;
+        let (width, height) = (first.width, first.height);
+
+        let mut child = Command::new("ffmpeg")
+            .args([
+                "-y",
+                "-f", "rawvideo",
+                "-pixel_format", "bgra",
+                "-video_size", &format!("{}x{}", width, height),
+                "-use_wallclock_as_timestamps", "1",
+                "-i", "pipe:0",
+                "-c:v", options.ffmpeg_codec_name(),
+                "-b:v", &format!("{}k", options.bitrate_kbps),
+                "-pix_fmt", "yuv420p",
+                "-movflags", "+faststart",
+                "-f", &options.container,
+                &output_path,
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("spawn ffmpeg: {}", e))?;
+
+        let mut stdin = child.stdin.take().ok_or("ffmpeg stdin unavailable")?;
+
+        let start = Instant::now();
+        let mut bytes_written: u64 = 0;
+        let mut last_progress_emit = Instant::now();
+
+        let mut size_warned = false;
+        let mut frame = Some(first);
+        loop {
+            let frame = match frame.take() {
+                Some(f) => f,
+                None => match rx.recv() {
+                    Ok(f) => f,
+                    Err(_) => break, // sender dropped → stop_recording was called
+                },
+            };
 
-        // target_pid > 0: INCLUDE mode (capture only target app's audio)
-        // target_pid == 0: EXCLUDE mode (capture all system audio minus Nexus)
-        let (pid, mode, mode_name) = if target_pid > 0 {
-            (target_pid, 0i32, "INCLUDE")  // PROCESS_LOOPBACK_MODE_INCLUDE_TARGET_PROCESS_TREE
-        } else {
-            (std::process::id(), 1i32, "EXCLUDE")  // PROCESS_LOOPBACK_MODE_EXCLUDE_TARGET_PROCESS_TREE
-        };
+            // ffmpeg was told `-video_size` from the first frame and expects
+            // every subsequent write to be exactly that many raw bytes; a
+            // mid-recording resize (the captured window changing size) would
+            // desync the rawvideo stream with no error from ffmpeg itself.
+            // Drop the mismatched frame instead of corrupting the rest of
+            // the recording, and tell the frontend once so it's not a
+            // silent gap.
+            if frame.width != width || frame.height != height {
+                if !size_warned {
+                    size_warned = true;
+                    let _ = app.emit(
+                        "recording-warning",
+                        format!(
+                            "Recording locked to {}x{}; frames at {}x{} are being dropped \
+                             (stop and restart the recording to pick up the new size)",
+                            width, height, frame.width, frame.height
+                        ),
+                    );
+                }
+                continue;
+            }
 
-        println!("[WASAPI] Attempting process loopback: {} mode, PID={}", mode_name, pid);
+            if stdin.write_all(&frame.data).is_err() {
+                // ffmpeg exited unexpectedly; nothing more we can do.
+                break;
+            }
+            bytes_written += frame.data.len() as u64;
 
-        // Step 1: Get the device's native format from the default render device.
-        // Used for EXCLUDE mode; INCLUDE mode queries the process loopback
-        // client's own mix format instead (see Step 3).
-        wasapi::initialize_mta();
-        let device_format = {
-            let enumerator = wasapi::DeviceEnumerator::new()
-                .map_err(|e| format!("DeviceEnumerator: {}", e))?;
-            let device = enumerator
-                .get_default_device(&wasapi::Direction::Render)
-                .map_err(|e| format!("get device: {}", e))?;
-            let mut client = device
-                .get_iaudioclient()
-                .map_err(|e| format!("get client: {}", e))?;
-            client
-                .get_mixformat()
-                .map_err(|e| format!("get format: {}", e))?
-        };
+            if last_progress_emit.elapsed().as_millis() >= 500 {
+                last_progress_emit = Instant::now();
+                let _ = app.emit(
+                    "recording-progress",
+                    &RecordingProgress {
+                        elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+                        bytes_written,
+                    },
+                );
+            }
+            let _ = frame.timestamp_ms; // presentation time lives in the wallclock read above
+        }
 
-        let sample_rate = device_format.get_samplespersec();
-        let channels = device_format.get_nchannels() as usize;
-        let bits = device_format.get_bitspersample();
-        let bytes_per_sample = (bits / 8) as usize;
+        // Closing stdin sends EOF so ffmpeg flushes and writes the
+        // container trailer before `wait()` returns.
+        drop(stdin);
+        let status = child.wait().map_err(|e| format!("ffmpeg wait: {}", e))?;
+        if !status.success() {
+            return Err(format!("ffmpeg exited with {}", status));
+        }
 
-        println!(
-            "[WASAPI] Device native format: {}Hz, {}ch, {}bit",
-            sample_rate, channels, bits
+        let _ = app.emit(
+            "recording-progress",
+            &RecordingProgress {
+                elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+                bytes_written,
+            },
         );
+        Ok(())
+    }
 
-        unsafe {
-            // Step 2: Activate process-excluded loopback client
-            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    #[tauri::command]
+    pub async fn start_recording(
+        app: AppHandle,
+        output_path: String,
+        options: RecordingOptions,
+    ) -> Result<(), String> {
+        if !CAPTURE_RUNNING.load(Ordering::SeqCst) {
+            return Err("Start a capture before recording it".into());
+        }
+        if RECORDING_TX.lock().unwrap().is_some() {
+            return Err("Recording already running".into());
+        }
 
-            let params = ProcessLoopbackActivationParams {
-                activation_type: 1,        // AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK
-                target_process_id: pid,
-                process_loopback_mode: mode,
-            };
+        // Bounded so a stalled encoder applies backpressure to itself, not
+        // to the WGC callback — on_frame_arrived uses try_send and simply
+        // drops the frame when this fills up.
+        let (tx, rx) = std::sync::mpsc::sync_channel::<RecordedFrame>(8);
+        *RECORDING_TX.lock().unwrap() = Some(tx);
 
-            let prop = PropVariantBlob {
-                vt: 0x0041, // VT_BLOB
-                reserved1: 0,
-                reserved2: 0,
-                reserved3: 0,
-                cb_size: std::mem::size_of::<ProcessLoopbackActivationParams>() as u32,
-                _pad: 0,
-                p_blob_data: &params as *const _ as *const u8,
-            };
+        let encoder_app = app.clone();
+        let handle = std::thread::spawn(move || {
+            run_recording_encoder(encoder_app, rx, output_path, options)
+        });
+        *RECORDING_THREAD_HANDLE.lock().unwrap() = Some(handle);
 
-            let event = CreateEventW(None, TRUE, FALSE, None)
-                .map_err(|e| format!("CreateEventW: {}", e))?;
+        Ok(())
+    }
 
-            let handler: IActivateAudioInterfaceCompletionHandler =
-                ActivateCompletionHandler {
-                    event: event.0 as isize,
-                }
-                .into();
-
-            let prop_ptr = &prop as *const PropVariantBlob as *const windows_core::PROPVARIANT;
-            let operation = ActivateAudioInterfaceAsync(
-                windows::core::w!("VAD\\Process_Loopback"),
-                &IAudioClient::IID,
-                Some(prop_ptr),
-                &handler,
-            )
-            .map_err(|e| format!("ActivateAudioInterfaceAsync: {}", e))?;
+    #[tauri::command]
+    pub async fn stop_recording() -> Result<(), String> {
+        // Dropping the sender closes the channel; the encoder thread's
+        // `rx.recv()` then returns Err, which finalizes the container.
+        RECORDING_TX.lock().unwrap().take();
+
+        if let Some(handle) = RECORDING_THREAD_HANDLE.lock().unwrap().take() {
+            handle
+                .join()
+                .map_err(|_| "Recording encoder thread panicked".to_string())??;
+        }
 
-            let _ = WaitForSingleObject(event, 5000);
-            let _ = CloseHandle(event);
+        Ok(())
+    }
 
-            let mut hr = windows::core::HRESULT(0);
-            let mut unk: Option<windows::core::IUnknown> = None;
-            operation
-                .GetActivateResult(&mut hr, &mut unk)
-                .map_err(|e| format!("GetActivateResult: {}", e))?;
-            hr.ok().map_err(|e| format!("Activation HRESULT: {}", e))?;
+    // ─── Snapshot (still frame → file / clipboard) ────────────────────
+
+    /// BGRA → RGBA is a channel swap, not a reshuffle of alpha's position.
+    /// `on_frame_arrived` already stores `LAST_FRAME` tightly packed, so the
+    /// stride derived here is normally exactly `width * 4`; the de-stride
+    /// still copies row by row so a padded buffer would come out packed
+    /// rather than be rejected by `RgbaImage::from_raw` as the wrong length.
+    fn bgra_to_rgba(bgra: &[u8], width: u32, height: u32) -> Vec<u8> {
+        let expected_row_bytes = width as usize * 4;
+        let stride = if height > 0 {
+            bgra.len() / height as usize
+        } else {
+            expected_row_bytes
+        };
 
-            let client: IAudioClient = unk
-                .ok_or("No audio client returned")?
-                .cast()
-                .map_err(|e| format!("Cast IAudioClient: {}", e))?;
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height as usize {
+            let row_start = y * stride;
+            let row_end = row_start + expected_row_bytes;
+            if row_end <= bgra.len() {
+                rgba.extend_from_slice(&bgra[row_start..row_end]);
+            }
+        }
 
-            println!(
-                "[WASAPI] Process loopback client activated ({} mode, PID={})",
-                mode_name, pid
-            );
+        for px in rgba.chunks_exact_mut(4) {
+            px.swap(0, 2);
+        }
+        rgba
+    }
 
-            // Step 3: Initialize with PCM stereo format + AUTOCONVERTPCM.
-            // Matches the Microsoft ApplicationLoopback official sample:
-            // LOOPBACK | EVENTCALLBACK | AUTOCONVERTPCM + PCM 16bit stereo.
-            // AUTOCONVERTPCM lets Windows convert from the process's actual
-            // output format to our requested format.
-            let capture_fmt = WAVEFORMATEX {
-                wFormatTag: 1, // WAVE_FORMAT_PCM
-                nChannels: 2,
-                nSamplesPerSec: 48000,
-                wBitsPerSample: 16,
-                nBlockAlign: 2 * 16 / 8, // nChannels * wBitsPerSample / 8
-                nAvgBytesPerSec: 48000 * 2 * 16 / 8,
-                cbSize: 0,
-            };
+    fn last_frame_rgba() -> Result<(Vec<u8>, u32, u32), String> {
+        let guard = LAST_FRAME.lock().unwrap();
+        let frame = guard.as_ref().ok_or("No frame captured yet")?;
+        Ok((
+            bgra_to_rgba(&frame.bgra, frame.width, frame.height),
+            frame.width,
+            frame.height,
+        ))
+    }
 
-            let _ = app.emit(
-                "wasapi-info",
-                format!(
-                    "WASAPI (process-{}, PID={}): {}Hz {}ch {}bit PCM",
-                    mode_name.to_lowercase(), pid, 48000, 2, 16
-                ),
-            );
+    /// Encode the most recently captured frame to `output_path` as PNG or
+    /// JPEG (inferred from `format`: "png" | "jpeg").
+    #[tauri::command]
+    pub async fn snapshot(format: String, output_path: String) -> Result<(), String> {
+        let (rgba, width, height) = last_frame_rgba()?;
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let img = image::RgbaImage::from_raw(width, height, rgba)
+                .ok_or("Captured frame buffer does not match its dimensions")?;
+
+            match format.as_str() {
+                "jpeg" | "jpg" => image::DynamicImage::ImageRgba8(img)
+                    .to_rgb8()
+                    .save_with_format(&output_path, image::ImageFormat::Jpeg)
+                    .map_err(|e| format!("save jpeg: {}", e)),
+                _ => img
+                    .save_with_format(&output_path, image::ImageFormat::Png)
+                    .map_err(|e| format!("save png: {}", e)),
+            }
+        })
+        .await
+        .map_err(|e| format!("spawn_blocking: {}", e))?
+    }
 
-            let init_flags: u32 = 0x00020000  // AUDCLNT_STREAMFLAGS_LOOPBACK
-                                | 0x00040000  // AUDCLNT_STREAMFLAGS_EVENTCALLBACK
-                                | 0x80000000; // AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM
-            client
-                .Initialize(
-                    AUDCLNT_SHAREMODE_SHARED,
-                    init_flags,
-                    0,
-                    0,
-                    &capture_fmt as *const WAVEFORMATEX,
-                    None,
-                )
-                .map_err(|e| format!("Initialize: {}", e))?;
-
-            // Get capture client and set up event handle
-            let capture_client: IAudioCaptureClient = client
-                .GetService()
-                .map_err(|e| format!("GetService(IAudioCaptureClient): {}", e))?;
-
-            let event_handle = CreateEventW(None, FALSE, FALSE, None)
-                .map_err(|e| format!("CreateEventW: {}", e))?;
-            client
-                .SetEventHandle(event_handle)
-                .map_err(|e| format!("SetEventHandle: {}", e))?;
-
-            // PCM 16-bit stereo 48kHz for the capture loop
-            let cap_channels = 2usize;
-            let cap_bytes_per_sample = 2usize; // 16-bit = 2 bytes
-            let cap_sample_rate = 48000u32;
-
-            // Start the stream
-            client.Start().map_err(|e| format!("Start: {}", e))?;
-            println!(
-                "[WASAPI] Process loopback capture started ({}Hz {}ch {}bit)",
-                cap_sample_rate, cap_channels, bits
-            );
+    /// Place the most recently captured frame on the OS clipboard as a
+    /// decoded RGBA image, so the user can paste it directly without an
+    /// intermediate file.
+    #[tauri::command]
+    pub async fn snapshot_to_clipboard() -> Result<(), String> {
+        let (rgba, width, height) = last_frame_rgba()?;
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+            clipboard
+                .set_image(arboard::ImageData {
+                    width: width as usize,
+                    height: height as usize,
+                    bytes: rgba.into(),
+                })
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| format!("spawn_blocking: {}", e))?
+    }
 
-            // Capture loop
-            let mut first_data = true;
-            while !stop_flag.load(Ordering::SeqCst) {
-                let wait_result = WaitForSingleObject(event_handle, 100);
-                if wait_result.0 == 258 { // WAIT_TIMEOUT
-                    continue;
-                }
+    // ─── WASAPI loopback capture (entry point) ───────────────────────
 
-                // Read all available packets
-                loop {
-                    let mut buffer_ptr: *mut u8 = std::ptr::null_mut();
-                    let mut frames_available: u32 = 0;
-                    let mut flags: u32 = 0;
+    /// `LoopbackSource` wrapper around `run_wasapi_loopback`, so
+    /// `start_capture`/`switch_capture_target` can drive Windows audio
+    /// through the same trait the portable cpal backend uses.
+    pub(crate) struct WasapiLoopback {
+        pub target_pid: u32,
+        pub device_id: Option<String>,
+        pub format: CaptureFormat,
+    }
 
-                    if capture_client
-                        .GetBuffer(
-                            &mut buffer_ptr,
-                            &mut frames_available,
-                            &mut flags,
-                            None,
-                            None,
-                        )
-                        .is_err()
-                        || frames_available == 0
-                    {
-                        break;
-                    }
+    impl LoopbackSource for WasapiLoopback {
+        fn start(&self, app: AppHandle, stop_flag: Arc<AtomicBool>) -> Result<(), String> {
+            run_wasapi_loopback(
+                app,
+                stop_flag,
+                self.target_pid,
+                self.device_id.clone(),
+                self.format.clone(),
+            )
+        }
+    }
 
-                    if first_data {
-                        first_data = false;
-                        println!(
-                            "[WASAPI] Process loopback: first {} frames captured",
-                            frames_available
-                        );
+    /// Look up a render (output) endpoint by id, falling back to the
+    /// default device — same shape as `find_capture_device` below, just
+    /// pointed at the other data flow.
+    fn find_render_device(
+        enumerator: &wasapi::DeviceEnumerator,
+        device_id: Option<&str>,
+    ) -> Result<wasapi::Device, String> {
+        use wasapi::Direction;
+
+        if let Some(id) = device_id {
+            if let Ok(collection) = enumerator.get_device_collection(&Direction::Render) {
+                for device in &collection {
+                    if let Ok(device) = device {
+                        if device.get_id().map(|d| d == id).unwrap_or(false) {
+                            return Ok(device);
+                        }
                     }
+                }
+            }
+            println!("[WASAPI] device '{}' not found, falling back to default", id);
+        }
 
-                    let total_samples = frames_available as usize * cap_channels;
-                    let buffer_bytes = total_samples * cap_bytes_per_sample;
-                    let raw_data = std::slice::from_raw_parts(buffer_ptr, buffer_bytes);
+        enumerator
+            .get_default_device(&Direction::Render)
+            .map_err(|e| format!("get default render device: {}", e))
+    }
 
-                    let all_samples = decode_samples(raw_data, cap_bytes_per_sample, total_samples);
-                    let stereo =
-                        downmix_to_stereo(&all_samples, cap_channels, frames_available as usize);
+    /// True if `device_id` names the system's current default render
+    /// endpoint. Treats an enumeration failure as "assume default", so a
+    /// transient lookup failure keeps the process-loopback path; a real
+    /// non-default selection is then not honored for that session.
+    fn is_default_render_device(device_id: &str) -> bool {
+        let Ok(enumerator) = wasapi::DeviceEnumerator::new() else {
+            return true;
+        };
+        let Ok(default) = enumerator.get_default_device(&wasapi::Direction::Render) else {
+            return true;
+        };
+        default.get_id().map(|id| id == device_id).unwrap_or(true)
+    }
 
-                    let payload = AudioPayload {
-                        data: stereo,
-                        sample_rate: cap_sample_rate,
-                        channels: 2,
-                        frames: frames_available,
-                    };
-                    let _ = app.emit("capture-audio", &payload);
+    /// Try process loopback first (Win10 2004+), fall back to regular.
+    /// target_pid > 0: INCLUDE mode (window share — capture only that app's audio)
+    /// target_pid == 0: EXCLUDE mode (monitor share — all system audio minus Nexus)
+    ///
+    /// `ActivateAudioInterfaceAsync(VAD\Process_Loopback, ...)` always binds
+    /// to the system's *default* render endpoint — there's no documented
+    /// way to scope process loopback to a specific output device. A
+    /// non-default `device_id` can't be honored there, so when one is
+    /// requested this skips straight to the regular-loopback path, which
+    /// does pick the requested device via `find_render_device`. Silently
+    /// activating process loopback and ignoring the selection would
+    /// capture the wrong device with no indication why.
+    fn run_wasapi_loopback(
+        app: AppHandle,
+        stop_flag: Arc<AtomicBool>,
+        target_pid: u32,
+        device_id: Option<String>,
+        format: CaptureFormat,
+    ) -> Result<(), String> {
+        let wants_non_default_device = device_id
+            .as_deref()
+            .map(|id| !is_default_render_device(id))
+            .unwrap_or(false);
 
-                    let _ = capture_client.ReleaseBuffer(frames_available);
+        if wants_non_default_device {
+            println!(
+                "[WASAPI] Non-default render device requested; process loopback only binds \
+                 to the default device, so using regular loopback instead."
+            );
+            let _ = app.emit(
+                "wasapi-info",
+                "WASAPI: using regular loopback to honor the selected output device",
+            );
+        } else {
+            match run_process_loopback(&app, &stop_flag, target_pid, device_id.as_deref(), &format)
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    println!(
+                        "[WASAPI] Process loopback unavailable: {}. \
+                         Falling back to regular loopback (voice echo possible).",
+                        e
+                    );
+                    let _ = app.emit(
+                        "wasapi-info",
+                        "WASAPI: regular loopback (process loopback unavailable)",
+                    );
                 }
             }
+        }
 
-            // Stop — explicit drop order matters for COM cleanup.
-            // Reset flushes all pending buffers, then Stop halts the stream.
-            // Both must succeed before releasing COM objects.
-            let _ = client.Reset();
-            let _ = client.Stop();
-            let _ = CloseHandle(event_handle);
-            drop(capture_client);
-            drop(client);
-            drop(operation);
-            CoUninitialize();
+        // The `wasapi` crate's higher-level `initialize_client` ties the
+        // format to whatever the device reports via `get_mixformat`, so
+        // there's no hook to request an arbitrary format here the way the
+        // raw `IAudioClient::Initialize` call in `run_process_loopback`
+        // allows — this path always negotiates the device's native format.
+        run_regular_loopback(app, stop_flag, device_id.as_deref())
+    }
 
-            println!("[WASAPI] Process-excluded loopback capture stopped");
-            Ok(())
+    /// sample_format bits/tag for a custom `WAVEFORMATEX`: ("i16" → 16-bit
+    /// PCM, anything else → 32-bit IEEE float).
+    fn format_bits_and_tag(sample_format: &str) -> (u16, u16) {
+        match sample_format {
+            "i16" => (16, 1),  // WAVE_FORMAT_PCM
+            _ => (32, 3),      // WAVE_FORMAT_IEEE_FLOAT
         }
     }
 
-    // ─── Regular WASAPI loopback (fallback) ──────────────────────────
+    /// List render + capture endpoints via the WASAPI `DeviceEnumerator`,
+    /// mirroring how `enumerate_capture_targets` surfaces video sources.
+    #[tauri::command]
+    pub async fn enumerate_audio_devices() -> Result<Vec<AudioDevice>, String> {
+        tauri::async_runtime::spawn_blocking(|| {
+            use wasapi::Direction;
+
+            wasapi::initialize_mta();
+            let enumerator =
+                wasapi::DeviceEnumerator::new().map_err(|e| format!("DeviceEnumerator: {}", e))?;
+
+            let mut devices = Vec::new();
+            for (direction, label) in [(Direction::Render, "render"), (Direction::Capture, "capture")] {
+                let default_id = enumerator
+                    .get_default_device(&direction)
+                    .ok()
+                    .and_then(|d| d.get_id().ok());
+
+                if let Ok(collection) = enumerator.get_device_collection(&direction) {
+                    for device in &collection {
+                        let Ok(device) = device else { continue };
+                        let Ok(id) = device.get_id() else { continue };
+                        let name = device.get_friendlyname().unwrap_or_else(|_| id.clone());
+                        let is_default = default_id.as_deref() == Some(id.as_str());
+                        devices.push(AudioDevice {
+                            id,
+                            name,
+                            direction: label.to_string(),
+                            is_default,
+                        });
+                    }
+                }
+            }
+
+            Ok(devices)
+        })
+        .await
+        .map_err(|e| format!("spawn_blocking: {}", e))?
+    }
+
+    /// Report the shared-mode mix format of `device_id` (or the default
+    /// render device): the one format the device handles natively, so
+    /// unlike cpal's `supported_input_configs()` range list this returns a
+    /// single entry. Regular loopback always captures in this format;
+    /// process loopback initializes with the requested `CaptureFormat`
+    /// instead and adds `AUTOCONVERTPCM` when it differs from this one.
+    #[tauri::command]
+    pub async fn supported_capture_formats(
+        device_id: Option<String>,
+    ) -> Result<Vec<CaptureFormat>, String> {
+        tauri::async_runtime::spawn_blocking(move || {
+            wasapi::initialize_mta();
+            let enumerator =
+                wasapi::DeviceEnumerator::new().map_err(|e| format!("DeviceEnumerator: {}", e))?;
+            let device = find_render_device(&enumerator, device_id.as_deref())?;
+            let mut client = device
+                .get_iaudioclient()
+                .map_err(|e| format!("get client: {}", e))?;
+            let format = client
+                .get_mixformat()
+                .map_err(|e| format!("get format: {}", e))?;
+
+            Ok(vec![CaptureFormat {
+                sample_rate: format.get_samplespersec(),
+                channels: format.get_nchannels() as u16,
+                // The WASAPI shared-mode mix format is IEEE float on every
+                // modern Windows audio engine.
+                sample_format: "f32".to_string(),
+            }])
+        })
+        .await
+        .map_err(|e| format!("spawn_blocking: {}", e))?
+    }
+
+    // ─── Microphone capture ────────────────────────────────────────────
     //
-    // Used when process-excluded loopback is unavailable (Windows < 10 2004).
-    // Captures ALL system audio, including voice chat (may cause echo).
+    // A plain (non-loopback) WASAPI capture stream on the `eCapture` data
+    // flow — same `wasapi` crate and buffer-draining shape as
+    // `run_regular_loopback`, just pointed at a microphone endpoint
+    // instead of the render-device loopback tap. It doesn't emit its own
+    // `capture-audio` event; it stages resampled samples into the shared
+    // mix buffer (see the "Microphone mixing" section above) for whichever
+    // loopback thread is running to pick up.
+
+    static MIC_STOP_FLAG: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+    static MIC_THREAD_HANDLE: Mutex<Option<std::thread::JoinHandle<()>>> = Mutex::new(None);
+
+    fn find_capture_device(
+        enumerator: &wasapi::DeviceEnumerator,
+        device_id: Option<&str>,
+    ) -> Result<wasapi::Device, String> {
+        use wasapi::Direction;
+
+        if let Some(id) = device_id {
+            if let Ok(collection) = enumerator.get_device_collection(&Direction::Capture) {
+                for device in &collection {
+                    if let Ok(device) = device {
+                        if device.get_id().map(|d| d == id).unwrap_or(false) {
+                            return Ok(device);
+                        }
+                    }
+                }
+            }
+            println!("[Mic] device '{}' not found, falling back to default", id);
+        }
+
+        enumerator
+            .get_default_device(&Direction::Capture)
+            .map_err(|e| format!("get default capture device: {}", e))
+    }
 
-    fn run_regular_loopback(app: AppHandle, stop_flag: Arc<AtomicBool>) -> Result<(), String> {
+    fn run_mic_capture(
+        app: AppHandle,
+        stop_flag: Arc<AtomicBool>,
+        device_id: Option<String>,
+    ) -> Result<(), String> {
         use wasapi::*;
 
-        // Initialize COM for this thread
         initialize_mta();
 
-        // Get default render (output) device for loopback
-        let enumerator =
-            DeviceEnumerator::new().map_err(|e| format!("DeviceEnumerator: {}", e))?;
-        let device = enumerator
-            .get_default_device(&Direction::Render)
-            .map_err(|e| format!("get device: {}", e))?;
+        let enumerator = DeviceEnumerator::new().map_err(|e| format!("DeviceEnumerator: {}", e))?;
+        let device = find_capture_device(&enumerator, device_id.as_deref())?;
 
         let mut audio_client = device
             .get_iaudioclient()
-            .map_err(|e| format!("get client: {}", e))?;
+            .map_err(|e| format!("get mic client: {}", e))?;
 
         let format = audio_client
             .get_mixformat()
-            .map_err(|e| format!("get format: {}", e))?;
+            .map_err(|e| format!("get mic format: {}", e))?;
 
         let device_sample_rate = format.get_samplespersec();
         let device_channels = format.get_nchannels() as usize;
         let bytes_per_sample = (format.get_bitspersample() / 8) as usize;
 
-        println!(
-            "[WASAPI] Regular loopback format: {}Hz, {}ch, {}bit ({}B/sample)",
-            device_sample_rate, device_channels, format.get_bitspersample(), bytes_per_sample
-        );
         let _ = app.emit(
-            "wasapi-info",
+            "mic-info",
             format!(
-                "WASAPI (regular): {}Hz {}ch {}bit",
+                "Mic: {}Hz {}ch {}bit",
                 device_sample_rate, device_channels, format.get_bitspersample()
             ),
         );
@@ -1149,137 +2501,1961 @@ mod platform {
         };
         audio_client
             .initialize_client(&format, &Direction::Capture, &mode)
-            .map_err(|e| format!("init client: {}", e))?;
+            .map_err(|e| format!("init mic client: {}", e))?;
 
         let capture_client = audio_client
             .get_audiocaptureclient()
-            .map_err(|e| format!("get capture client: {}", e))?;
+            .map_err(|e| format!("get mic capture client: {}", e))?;
 
         let event = audio_client
             .set_get_eventhandle()
-            .map_err(|e| format!("set event: {}", e))?;
+            .map_err(|e| format!("set mic event: {}", e))?;
 
         audio_client
             .start_stream()
-            .map_err(|e| format!("start stream: {}", e))?;
+            .map_err(|e| format!("start mic stream: {}", e))?;
 
-        println!("[WASAPI] Regular loopback capture started");
+        println!("[Mic] capture started");
 
         let mut sample_queue: VecDeque<u8> = VecDeque::new();
-        let mut first_data = true;
 
         while !stop_flag.load(Ordering::SeqCst) {
             if event.wait_for_event(100).is_err() {
                 continue;
             }
 
-            match capture_client.read_from_device_to_deque(&mut sample_queue) {
-                Ok(_buffer_info) => {
-                    if sample_queue.is_empty() {
-                        continue;
-                    }
+            if capture_client.read_from_device_to_deque(&mut sample_queue).is_err()
+                || sample_queue.is_empty()
+            {
+                continue;
+            }
 
-                    let total_bytes = sample_queue.len();
-                    let total_samples = total_bytes / bytes_per_sample;
-                    let frame_count = total_samples / device_channels;
+            let total_samples = sample_queue.len() / bytes_per_sample;
+            let frame_count = total_samples / device_channels;
+            if frame_count == 0 {
+                continue;
+            }
 
-                    if frame_count == 0 {
-                        continue;
-                    }
+            let raw: Vec<u8> = sample_queue.drain(..total_samples * bytes_per_sample).collect();
+            let all_samples = decode_samples(&raw, bytes_per_sample, total_samples);
+            let stereo = downmix_to_stereo(&all_samples, device_channels, frame_count);
+            let resampled =
+                super::resample_stereo(&stereo, device_sample_rate, super::MIX_SAMPLE_RATE);
+            super::stage_mic_samples(&resampled);
+        }
 
-                    if first_data {
-                        first_data = false;
-                        println!(
-                            "[WASAPI] First audio data: {} bytes, {} frames, {} samples",
-                            total_bytes, frame_count, total_samples
-                        );
-                    }
+        let _ = audio_client.stop_stream();
+        println!("[Mic] capture stopped");
+        Ok(())
+    }
 
-                    // Decode raw bytes → f32 from the deque
-                    let mut all_samples = Vec::with_capacity(total_samples);
-                    for _ in 0..total_samples {
-                        if sample_queue.len() >= bytes_per_sample {
-                            let sample = if bytes_per_sample == 4 {
-                                let b0 = sample_queue.pop_front().unwrap();
-                                let b1 = sample_queue.pop_front().unwrap();
-                                let b2 = sample_queue.pop_front().unwrap();
-                                let b3 = sample_queue.pop_front().unwrap();
-                                f32::from_le_bytes([b0, b1, b2, b3])
-                            } else if bytes_per_sample == 2 {
-                                let b0 = sample_queue.pop_front().unwrap();
-                                let b1 = sample_queue.pop_front().unwrap();
-                                i16::from_le_bytes([b0, b1]) as f32 / 32768.0
-                            } else {
-                                for _ in 0..bytes_per_sample {
-                                    sample_queue.pop_front();
-                                }
-                                0.0
-                            };
-                            all_samples.push(sample);
-                        }
-                    }
+    /// Spawn the mic-input thread and arm the shared mix buffer. Call
+    /// only while a loopback thread is (or is about to be) running —
+    /// staged mic samples are otherwise never consumed.
+    fn start_mic_thread(
+        app: AppHandle,
+        device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+    ) {
+        super::start_mic_mix(mic_gain, loopback_gain);
 
-                    let stereo = downmix_to_stereo(&all_samples, device_channels, frame_count);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        *MIC_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
 
-                    let payload = AudioPayload {
-                        data: stereo,
-                        sample_rate: device_sample_rate,
-                        channels: 2,
-                        frames: frame_count as u32,
-                    };
-                    let _ = app.emit("capture-audio", &payload);
-                }
-                Err(_) => {
-                    continue;
-                }
+        let handle = std::thread::spawn(move || {
+            if let Err(e) = run_mic_capture(app, stop_flag, device_id) {
+                eprintln!("Mic capture error: {}", e);
             }
-        }
-
-        audio_client
-            .stop_stream()
-            .map_err(|e| format!("stop stream: {}", e))?;
+        });
+        *MIC_THREAD_HANDLE.lock().unwrap() = Some(handle);
+    }
 
-        println!("[WASAPI] Regular loopback capture stopped");
-        Ok(())
+    fn stop_mic_thread() {
+        if let Some(flag) = MIC_STOP_FLAG.lock().unwrap().take() {
+            flag.store(true, Ordering::SeqCst);
+        }
+        if let Some(handle) = MIC_THREAD_HANDLE.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        super::stop_mic_mix();
     }
-}
 
-#[cfg(target_os = "windows")]
-pub use platform::*;
+    // ─── Process loopback (Windows 10 2004+) ──────────────────────────
+    //
+    // target_pid > 0: INCLUDE mode — capture only the target process tree's audio
+    //                 (window share: "share only the selected app's audio")
+    // target_pid == 0: EXCLUDE mode — capture all system audio EXCEPT our own process
+    //                  (monitor share: "share all system audio minus Nexus")
 
-// ─── Stub implementations for non-Windows ───────────────────────────
-#[cfg(not(target_os = "windows"))]
-mod stub {
-    use super::CaptureTarget;
+    /// Raw PROPVARIANT layout for VT_BLOB on x64.
+    /// Used to pass AUDIOCLIENT_ACTIVATION_PARAMS to ActivateAudioInterfaceAsync.
+    #[repr(C)]
+    struct PropVariantBlob {
+        vt: u16,             // VT_BLOB = 0x0041
+        reserved1: u16,
+        reserved2: u16,
+        reserved3: u16,
+        cb_size: u32,        // BLOB.cbSize
+        _pad: u32,           // alignment padding on x64
+        p_blob_data: *const u8, // BLOB.pBlobData
+    }
 
-    #[tauri::command]
-    pub async fn enumerate_capture_targets() -> Result<Vec<CaptureTarget>, String> {
-        Err("Native capture is only supported on Windows".into())
+    /// Activation params for process loopback.
+    #[repr(C)]
+    struct ProcessLoopbackActivationParams {
+        activation_type: i32,        // AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK = 1
+        target_process_id: u32,      // target PID (INCLUDE) or our PID (EXCLUDE)
+        process_loopback_mode: i32,  // 0 = INCLUDE_TARGET_PROCESS_TREE, 1 = EXCLUDE_TARGET_PROCESS_TREE
     }
 
-    #[tauri::command]
-    pub async fn start_capture(
-        _app: tauri::AppHandle,
-        _target_id: String,
-        _fps: u32,
-        _capture_audio: bool,
-        _target_process_id: u32,
-    ) -> Result<(), String> {
-        Err("Native capture is only supported on Windows".into())
+    /// COM completion handler for ActivateAudioInterfaceAsync.
+    #[windows::core::implement(IActivateAudioInterfaceCompletionHandler)]
+    struct ActivateCompletionHandler {
+        event: isize, // HANDLE as isize (Send-safe)
     }
 
-    #[tauri::command]
-    pub async fn stop_capture() -> Result<(), String> {
-        Err("Native capture is only supported on Windows".into())
+    impl IActivateAudioInterfaceCompletionHandler_Impl for ActivateCompletionHandler_Impl {
+        fn ActivateCompleted(
+            &self,
+            _operation: Option<&IActivateAudioInterfaceAsyncOperation>,
+        ) -> windows::core::Result<()> {
+            unsafe {
+                let _ = SetEvent(HANDLE(self.event as *mut std::ffi::c_void));
+            }
+            Ok(())
+        }
     }
 
-    #[tauri::command]
-    pub async fn switch_capture_target(
-        _app: tauri::AppHandle,
-        _target_id: String,
-        _fps: u32,
-        _target_process_id: u32,
+    /// HRESULT `AUDCLNT_E_DEVICE_INVALIDATED` (0x88890004) — the device the
+    /// client was initialized against disappeared or stopped being the
+    /// default (unplugged, disabled, or the user switched output device).
+    const AUDCLNT_E_DEVICE_INVALIDATED: i32 = 0x8889_0004u32 as i32;
+
+    fn run_process_loopback(
+        app: &AppHandle,
+        stop_flag: &Arc<AtomicBool>,
+        target_pid: u32,
+        device_id: Option<&str>,
+        requested_format: &CaptureFormat,
     ) -> Result<(), String> {
+        use windows::core::Interface;
+        use windows::Win32::Media::Audio::*;
+        use windows::Win32::System::Com::*;
+
+        // target_pid > 0: INCLUDE mode (capture only target app's audio)
+        // target_pid == 0: EXCLUDE mode (capture all system audio minus Nexus)
+        let (pid, mode, mode_name) = if target_pid > 0 {
+            (target_pid, 0i32, "INCLUDE")  // PROCESS_LOOPBACK_MODE_INCLUDE_TARGET_PROCESS_TREE
+        } else {
+            (std::process::id(), 1i32, "EXCLUDE")  // PROCESS_LOOPBACK_MODE_EXCLUDE_TARGET_PROCESS_TREE
+        };
+
+        println!("[WASAPI] Attempting process loopback: {} mode, PID={}", mode_name, pid);
+
+        // Reconnect loop: on AUDCLNT_E_DEVICE_INVALIDATED (default device
+        // swapped or unplugged mid-capture) we tear down and re-run the
+        // whole activation/initialize sequence against whatever is now the
+        // default, instead of spinning on a dead client until the frontend
+        // does its own stop_capture/start_capture cycle.
+        'reconnect: loop {
+            // Step 1: Get the device's native format from the selected (or default)
+            // render device. In both modes it is compared with the requested
+            // format in Step 3 to decide whether AUTOCONVERTPCM is needed.
+            // Note: `ActivateAudioInterfaceAsync` below always activates the
+            // system's process-loopback virtual device, not a specific render
+            // endpoint, so this function is only ever reached with `device_id`
+            // unset or equal to the current default (see `run_wasapi_loopback`,
+            // which routes a non-default selection to regular loopback
+            // instead) — `device_id` here only affects which device this
+            // format probe (and therefore the logged/emitted info) reflects.
+            wasapi::initialize_mta();
+            let device_format = {
+                let enumerator = wasapi::DeviceEnumerator::new()
+                    .map_err(|e| format!("DeviceEnumerator: {}", e))?;
+                let device = find_render_device(&enumerator, device_id)?;
+                let mut client = device
+                    .get_iaudioclient()
+                    .map_err(|e| format!("get client: {}", e))?;
+                client
+                    .get_mixformat()
+                    .map_err(|e| format!("get format: {}", e))?
+            };
+
+            let sample_rate = device_format.get_samplespersec();
+            let channels = device_format.get_nchannels() as usize;
+            let bits = device_format.get_bitspersample();
+            let bytes_per_sample = (bits / 8) as usize;
+
+            println!(
+                "[WASAPI] Device native format: {}Hz, {}ch, {}bit",
+                sample_rate, channels, bits
+            );
+
+            unsafe {
+                // Step 2: Activate process-excluded loopback client
+                let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+                let params = ProcessLoopbackActivationParams {
+                    activation_type: 1,        // AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK
+                    target_process_id: pid,
+                    process_loopback_mode: mode,
+                };
+
+                let prop = PropVariantBlob {
+                    vt: 0x0041, // VT_BLOB
+                    reserved1: 0,
+                    reserved2: 0,
+                    reserved3: 0,
+                    cb_size: std::mem::size_of::<ProcessLoopbackActivationParams>() as u32,
+                    _pad: 0,
+                    p_blob_data: &params as *const _ as *const u8,
+                };
+
+                let event = CreateEventW(None, TRUE, FALSE, None)
+                    .map_err(|e| format!("CreateEventW: {}", e))?;
+
+                let handler: IActivateAudioInterfaceCompletionHandler =
+                    ActivateCompletionHandler {
+                        event: event.0 as isize,
+                    }
+                    .into();
+
+                let prop_ptr = &prop as *const PropVariantBlob as *const windows_core::PROPVARIANT;
+                let operation = ActivateAudioInterfaceAsync(
+                    windows::core::w!("VAD\\Process_Loopback"),
+                    &IAudioClient::IID,
+                    Some(prop_ptr),
+                    &handler,
+                )
+                .map_err(|e| format!("ActivateAudioInterfaceAsync: {}", e))?;
+
+                let _ = WaitForSingleObject(event, 5000);
+                let _ = CloseHandle(event);
+
+                let mut hr = windows::core::HRESULT(0);
+                let mut unk: Option<windows::core::IUnknown> = None;
+                operation
+                    .GetActivateResult(&mut hr, &mut unk)
+                    .map_err(|e| format!("GetActivateResult: {}", e))?;
+                hr.ok().map_err(|e| format!("Activation HRESULT: {}", e))?;
+
+                let client: IAudioClient = unk
+                    .ok_or("No audio client returned")?
+                    .cast()
+                    .map_err(|e| format!("Cast IAudioClient: {}", e))?;
+
+                println!(
+                    "[WASAPI] Process loopback client activated ({} mode, PID={})",
+                    mode_name, pid
+                );
+
+                // Step 3: Initialize with the requested (or default 48kHz/16-bit
+                // stereo) format. Matches the Microsoft ApplicationLoopback
+                // official sample in shape: LOOPBACK | EVENTCALLBACK, plus
+                // AUTOCONVERTPCM only when the requested format doesn't match
+                // what the device already natively reports — AUTOCONVERTPCM
+                // lets Windows convert from the process's actual output format
+                // to ours, so it's unnecessary (and skipped) when they already
+                // agree.
+                let req_channels = requested_format.channels;
+                let req_sample_rate = requested_format.sample_rate;
+                let (req_bits, req_format_tag) = format_bits_and_tag(&requested_format.sample_format);
+
+                let capture_fmt = WAVEFORMATEX {
+                    wFormatTag: req_format_tag,
+                    nChannels: req_channels,
+                    nSamplesPerSec: req_sample_rate,
+                    wBitsPerSample: req_bits,
+                    nBlockAlign: req_channels * req_bits / 8,
+                    nAvgBytesPerSec: req_sample_rate * req_channels as u32 * req_bits as u32 / 8,
+                    cbSize: 0,
+                };
+
+                let _ = app.emit(
+                    "wasapi-info",
+                    format!(
+                        "WASAPI (process-{}, PID={}): {}Hz {}ch {}bit {}",
+                        mode_name.to_lowercase(),
+                        pid,
+                        req_sample_rate,
+                        req_channels,
+                        req_bits,
+                        requested_format.sample_format,
+                    ),
+                );
+
+                let natively_supported = sample_rate == req_sample_rate
+                    && channels == req_channels as usize
+                    && bits == req_bits;
+
+                let base_flags: u32 = 0x00020000 // AUDCLNT_STREAMFLAGS_LOOPBACK
+                                    | 0x00040000; // AUDCLNT_STREAMFLAGS_EVENTCALLBACK
+                let autoconvert_flags: u32 = base_flags | 0x80000000; // AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM
+                let init_flags = if natively_supported { base_flags } else { autoconvert_flags };
+
+                let init_result = client.Initialize(
+                    AUDCLNT_SHAREMODE_SHARED,
+                    init_flags,
+                    0,
+                    0,
+                    &capture_fmt as *const WAVEFORMATEX,
+                    None,
+                );
+                // A "natively supported" format can still fail to initialize
+                // without AUTOCONVERTPCM (sample rate matches, but the engine's
+                // internal pipeline still wants to resample) — retry once with
+                // it forced on before giving up.
+                let init_result = match init_result {
+                    Ok(()) => Ok(()),
+                    Err(_) if natively_supported => client.Initialize(
+                        AUDCLNT_SHAREMODE_SHARED,
+                        autoconvert_flags,
+                        0,
+                        0,
+                        &capture_fmt as *const WAVEFORMATEX,
+                        None,
+                    ),
+                    Err(e) => Err(e),
+                };
+                init_result.map_err(|e| format!("Initialize: {}", e))?;
+
+                // Get capture client and set up event handle
+                let capture_client: IAudioCaptureClient = client
+                    .GetService()
+                    .map_err(|e| format!("GetService(IAudioCaptureClient): {}", e))?;
+
+                let event_handle = CreateEventW(None, FALSE, FALSE, None)
+                    .map_err(|e| format!("CreateEventW: {}", e))?;
+                client
+                    .SetEventHandle(event_handle)
+                    .map_err(|e| format!("SetEventHandle: {}", e))?;
+
+                // Matches the negotiated `capture_fmt` above, for the capture loop.
+                let cap_channels = req_channels as usize;
+                let cap_bytes_per_sample = (req_bits / 8) as usize;
+                let cap_sample_rate = req_sample_rate;
+
+                // Start the stream
+                client.Start().map_err(|e| format!("Start: {}", e))?;
+                println!(
+                    "[WASAPI] Process loopback capture started ({}Hz {}ch {}bit)",
+                    cap_sample_rate, cap_channels, req_bits
+                );
+
+                // Capture loop
+                let mut first_data = true;
+                let mut invalidated = false;
+                while !stop_flag.load(Ordering::SeqCst) {
+                    let wait_result = WaitForSingleObject(event_handle, 100);
+                    if wait_result.0 == 258 { // WAIT_TIMEOUT
+                        continue;
+                    }
+
+                    // Read all available packets
+                    loop {
+                        let mut buffer_ptr: *mut u8 = std::ptr::null_mut();
+                        let mut frames_available: u32 = 0;
+                        let mut flags: u32 = 0;
+
+                        let get_buffer_result = capture_client.GetBuffer(
+                            &mut buffer_ptr,
+                            &mut frames_available,
+                            &mut flags,
+                            None,
+                            None,
+                        );
+
+                        match get_buffer_result {
+                            Err(e) if e.code().0 == AUDCLNT_E_DEVICE_INVALIDATED => {
+                                invalidated = true;
+                                break;
+                            }
+                            Err(_) => break,
+                            Ok(()) if frames_available == 0 => break,
+                            Ok(()) => {}
+                        }
+
+                        if first_data {
+                            first_data = false;
+                            println!(
+                                "[WASAPI] Process loopback: first {} frames captured",
+                                frames_available
+                            );
+                        }
+
+                        let total_samples = frames_available as usize * cap_channels;
+                        let buffer_bytes = total_samples * cap_bytes_per_sample;
+                        let raw_data = std::slice::from_raw_parts(buffer_ptr, buffer_bytes);
+
+                        let all_samples = decode_samples(raw_data, cap_bytes_per_sample, total_samples);
+                        let stereo =
+                            downmix_to_stereo(&all_samples, cap_channels, frames_available as usize);
+
+                        let (mixed, mix_rate) = mix_in_mic(&stereo, cap_sample_rate);
+                        let mixed_frames = (mixed.len() / 2) as u32;
+                        let payload = AudioPayload {
+                            data: mixed,
+                            sample_rate: mix_rate,
+                            channels: 2,
+                            frames: mixed_frames,
+                        };
+                        let _ = app.emit("capture-audio", &payload);
+
+                        let _ = capture_client.ReleaseBuffer(frames_available);
+                    }
+
+                    if invalidated {
+                        break;
+                    }
+                }
+
+                // Stop — explicit drop order matters for COM cleanup.
+                // Reset flushes all pending buffers, then Stop halts the stream.
+                // Both must succeed before releasing COM objects.
+                let _ = client.Reset();
+                let _ = client.Stop();
+                let _ = CloseHandle(event_handle);
+                drop(capture_client);
+                drop(client);
+                drop(operation);
+                CoUninitialize();
+
+                if invalidated {
+                    if stop_flag.load(Ordering::SeqCst) {
+                        println!("[WASAPI] Process-excluded loopback capture stopped");
+                        return Ok(());
+                    }
+                    println!("[WASAPI] Process loopback device invalidated, reconnecting to new default device");
+                    let _ = app.emit("wasapi-info", "WASAPI: audio device changed, reconnecting...");
+                    continue 'reconnect;
+                }
+
+                println!("[WASAPI] Process-excluded loopback capture stopped");
+                return Ok(());
+            }
+        }
+    }
+
+    // ─── Regular WASAPI loopback (fallback) ──────────────────────────
+    //
+    // Used when process-excluded loopback is unavailable (Windows < 10 2004),
+    // or when a non-default output device is selected (see `run_wasapi_loopback`).
+    // Captures ALL system audio, including voice chat (may cause echo).
+
+    /// The `wasapi` crate surfaces HRESULTs as formatted error strings
+    /// rather than a typed variant, so device-invalidation is detected the
+    /// same stringly-typed way the rest of this module already reports
+    /// errors — by looking for the HRESULT (or its symbolic name) in the
+    /// `Display` output.
+    fn is_device_invalidated(err: &str) -> bool {
+        err.contains("88890004") || err.to_uppercase().contains("DEVICE_INVALIDATED")
+    }
+
+    /// Captures system audio via the regular (non-process-excluded) WASAPI
+    /// loopback tap. Re-binds to a fresh default (or user-selected) device
+    /// whenever the stream reports `AUDCLNT_E_DEVICE_INVALIDATED`, so a
+    /// default-device switch or unplug mid-capture recovers on its own
+    /// instead of leaving the thread reading from a dead client.
+    fn run_regular_loopback(
+        app: AppHandle,
+        stop_flag: Arc<AtomicBool>,
+        device_id: Option<&str>,
+    ) -> Result<(), String> {
+        use wasapi::*;
+
+        // Initialize COM for this thread
+        initialize_mta();
+
+        let mut reconnecting = false;
+
+        'reconnect: loop {
+            // Get the selected (or default) render (output) device for loopback
+            let enumerator =
+                DeviceEnumerator::new().map_err(|e| format!("DeviceEnumerator: {}", e))?;
+            let device = find_render_device(&enumerator, device_id)?;
+
+            let mut audio_client = device
+                .get_iaudioclient()
+                .map_err(|e| format!("get client: {}", e))?;
+
+            let format = audio_client
+                .get_mixformat()
+                .map_err(|e| format!("get format: {}", e))?;
+
+            let device_sample_rate = format.get_samplespersec();
+            let device_channels = format.get_nchannels() as usize;
+            let bytes_per_sample = (format.get_bitspersample() / 8) as usize;
+
+            println!(
+                "[WASAPI] Regular loopback format: {}Hz, {}ch, {}bit ({}B/sample)",
+                device_sample_rate, device_channels, format.get_bitspersample(), bytes_per_sample
+            );
+            let _ = app.emit(
+                "wasapi-info",
+                format!(
+                    "WASAPI (regular){}: {}Hz {}ch {}bit",
+                    if reconnecting { " reconnected" } else { "" },
+                    device_sample_rate, device_channels, format.get_bitspersample()
+                ),
+            );
+
+            let mode = StreamMode::EventsShared {
+                autoconvert: true,
+                buffer_duration_hns: 0,
+            };
+            audio_client
+                .initialize_client(&format, &Direction::Capture, &mode)
+                .map_err(|e| format!("init client: {}", e))?;
+
+            let capture_client = audio_client
+                .get_audiocaptureclient()
+                .map_err(|e| format!("get capture client: {}", e))?;
+
+            let event = audio_client
+                .set_get_eventhandle()
+                .map_err(|e| format!("set event: {}", e))?;
+
+            audio_client
+                .start_stream()
+                .map_err(|e| format!("start stream: {}", e))?;
+
+            println!("[WASAPI] Regular loopback capture started");
+
+            let mut sample_queue: VecDeque<u8> = VecDeque::new();
+            let mut first_data = true;
+            let mut invalidated = false;
+
+            while !stop_flag.load(Ordering::SeqCst) {
+                if event.wait_for_event(100).is_err() {
+                    continue;
+                }
+
+                match capture_client.read_from_device_to_deque(&mut sample_queue) {
+                    Ok(_buffer_info) => {
+                        if sample_queue.is_empty() {
+                            continue;
+                        }
+
+                        let total_bytes = sample_queue.len();
+                        let total_samples = total_bytes / bytes_per_sample;
+                        let frame_count = total_samples / device_channels;
+
+                        if frame_count == 0 {
+                            continue;
+                        }
+
+                        if first_data {
+                            first_data = false;
+                            println!(
+                                "[WASAPI] First audio data: {} bytes, {} frames, {} samples",
+                                total_bytes, frame_count, total_samples
+                            );
+                        }
+
+                        // Decode raw bytes → f32 from the deque
+                        let mut all_samples = Vec::with_capacity(total_samples);
+                        for _ in 0..total_samples {
+                            if sample_queue.len() >= bytes_per_sample {
+                                let sample = if bytes_per_sample == 4 {
+                                    let b0 = sample_queue.pop_front().unwrap();
+                                    let b1 = sample_queue.pop_front().unwrap();
+                                    let b2 = sample_queue.pop_front().unwrap();
+                                    let b3 = sample_queue.pop_front().unwrap();
+                                    f32::from_le_bytes([b0, b1, b2, b3])
+                                } else if bytes_per_sample == 2 {
+                                    let b0 = sample_queue.pop_front().unwrap();
+                                    let b1 = sample_queue.pop_front().unwrap();
+                                    i16::from_le_bytes([b0, b1]) as f32 / 32768.0
+                                } else {
+                                    for _ in 0..bytes_per_sample {
+                                        sample_queue.pop_front();
+                                    }
+                                    0.0
+                                };
+                                all_samples.push(sample);
+                            }
+                        }
+
+                        let stereo = downmix_to_stereo(&all_samples, device_channels, frame_count);
+                        let (mixed, mix_rate) = mix_in_mic(&stereo, device_sample_rate);
+                        let mixed_frames = (mixed.len() / 2) as u32;
+
+                        let payload = AudioPayload {
+                            data: mixed,
+                            sample_rate: mix_rate,
+                            channels: 2,
+                            frames: mixed_frames,
+                        };
+                        let _ = app.emit("capture-audio", &payload);
+                    }
+                    Err(e) if is_device_invalidated(&e.to_string()) => {
+                        invalidated = true;
+                        break;
+                    }
+                    Err(_) => {
+                        continue;
+                    }
+                }
+            }
+
+            let _ = audio_client.stop_stream();
+
+            if invalidated && !stop_flag.load(Ordering::SeqCst) {
+                println!("[WASAPI] Regular loopback device invalidated, reconnecting to new default device");
+                let _ = app.emit("wasapi-info", "WASAPI: audio device changed, reconnecting...");
+                reconnecting = true;
+                continue 'reconnect;
+            }
+
+            println!("[WASAPI] Regular loopback capture stopped");
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub use platform::*;
+
+// ─── Linux video capture (xdg-desktop-portal ScreenCast + PipeWire) ─────
+//
+// Audio loopback on Linux already goes through the portable cpal backend
+// below (`stub::CpalLoopback`, same as every other non-Windows target), so
+// this module only covers video — the one piece that's genuinely
+// OS-specific here. `stub`'s `start_capture`/`stop_capture`/
+// `switch_capture_target`/`enumerate_capture_targets` delegate into it on
+// Linux (and into `macos_capture` on macOS) and keep the "unsupported"
+// stub body everywhere else, the same way `platform`/`stub` split on
+// Windows vs not.
+#[cfg(target_os = "linux")]
+mod linux_capture {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use ashpd::desktop::screencast::{CursorMode, PersistMode, Screencast, SourceType};
+    use ashpd::desktop::Session;
+    use base64::Engine;
+    use pipewire::spa::param::video::VideoInfoRaw;
+    use pipewire::stream::{Stream, StreamFlags};
+    use tauri::{AppHandle, Emitter};
+
+    use super::{CaptureTarget, FramePayload};
+
+    /// A running portal + PipeWire capture. The portal `Session` is kept
+    /// alive for the capture's whole lifetime (instead of being dropped
+    /// once `Start` returns a node id) so `switch_target` can re-run
+    /// `select_sources` against the *same* session — that's what avoids a
+    /// second permission prompt on every target switch.
+    struct LinuxCaptureSession {
+        portal_session: Session<'static, Screencast<'static>>,
+        stop_flag: Arc<AtomicBool>,
+        thread: std::thread::JoinHandle<()>,
+    }
+
+    static CAPTURE_SESSION: Mutex<Option<LinuxCaptureSession>> = Mutex::new(None);
+
+    /// The portal's own system picker does the actual window/monitor
+    /// selection UI — `target_id` only narrows which kind of source it
+    /// offers, same distinction `CaptureTarget::target_type` draws on
+    /// Windows.
+    fn source_type_for(target_id: &str) -> SourceType {
+        if target_id.starts_with("window:") {
+            SourceType::Window.into()
+        } else {
+            SourceType::Monitor.into()
+        }
+    }
+
+    /// `SelectSources` + `Start` against an existing session, returning the
+    /// PipeWire node id the compositor is streaming to. Split out from
+    /// session creation so `switch_target` can call this again on the same
+    /// session without a second prompt.
+    async fn select_and_start(
+        proxy: &Screencast<'_>,
+        session: &Session<'_, Screencast<'_>>,
+        target_id: &str,
+    ) -> Result<u32, String> {
+        proxy
+            .select_sources(
+                session,
+                CursorMode::Embedded,
+                source_type_for(target_id),
+                false, // multiple: this command only ever captures one target
+                None,  // restore_token: always re-prompt for now
+                PersistMode::DoNot,
+            )
+            .await
+            .map_err(|e| format!("select sources: {}", e))?;
+
+        let response = proxy
+            .start(session, None)
+            .await
+            .map_err(|e| format!("portal permission denied: {}", e))?
+            .response()
+            .map_err(|e| format!("portal permission denied: {}", e))?;
+
+        response
+            .streams()
+            .first()
+            .map(|s| s.pipe_wire_node_id())
+            .ok_or_else(|| "portal returned no PipeWire stream".to_string())
+    }
+
+    /// Build the negotiation pod for a BGRx video format at up to `fps`,
+    /// size left as a wide range since the portal (not us) ultimately picks
+    /// the captured monitor/window resolution.
+    fn video_format_pod(fps: u32) -> Result<Vec<u8>, String> {
+        use pipewire::spa::param::format::{FormatProperties, MediaSubtype, MediaType};
+        use pipewire::spa::param::ParamType;
+        use pipewire::spa::pod::serialize::PodSerializer;
+        use pipewire::spa::pod::Value;
+        use pipewire::spa::utils::{Fraction, Rectangle, SpaTypes};
+
+        let obj = pipewire::spa::pod::object!(
+            SpaTypes::ObjectParamFormat,
+            ParamType::EnumFormat,
+            pipewire::spa::pod::property!(FormatProperties::MediaType, Id, MediaType::Video),
+            pipewire::spa::pod::property!(FormatProperties::MediaSubtype, Id, MediaSubtype::Raw),
+            pipewire::spa::pod::property!(
+                FormatProperties::VideoFormat,
+                Id,
+                pipewire::spa::param::video::VideoFormat::BGRx
+            ),
+            pipewire::spa::pod::property!(
+                FormatProperties::VideoSize,
+                Choice,
+                Range,
+                Rectangle,
+                Rectangle { width: 1920, height: 1080 },
+                Rectangle { width: 1, height: 1 },
+                Rectangle { width: 8192, height: 8192 }
+            ),
+            pipewire::spa::pod::property!(
+                FormatProperties::VideoFramerate,
+                Choice,
+                Range,
+                Fraction,
+                Fraction { num: fps.clamp(1, 60), denom: 1 },
+                Fraction { num: 0, denom: 1 },
+                Fraction { num: 1000, denom: 1 }
+            ),
+        );
+
+        PodSerializer::serialize(std::io::Cursor::new(Vec::new()), &Value::Object(obj))
+            .map(|(cursor, _)| cursor.into_inner())
+            .map_err(|e| format!("serialize format pod: {:?}", e))
+    }
+
+    /// Drive the PipeWire side of a capture on its own thread: bind to
+    /// `node_id`, JPEG-encode each negotiated BGRx frame, and emit
+    /// `capture-frame` — the same payload `platform::CaptureHandler`
+    /// produces from WGC on Windows.
+    fn run_pipewire_capture(
+        app: AppHandle,
+        node_id: u32,
+        fps: u32,
+        stop_flag: Arc<AtomicBool>,
+    ) -> Result<(), String> {
+        let mainloop = pipewire::MainLoop::new().map_err(|e| format!("MainLoop: {}", e))?;
+        let context = pipewire::Context::new(&mainloop).map_err(|e| format!("Context: {}", e))?;
+        let core = context
+            .connect(None)
+            .map_err(|e| format!("PipeWire connect: {}", e))?;
+
+        let stream = Stream::new(
+            &core,
+            "nexus-capture",
+            pipewire::properties! {
+                *pipewire::keys::MEDIA_TYPE => "Video",
+                *pipewire::keys::MEDIA_CATEGORY => "Capture",
+                *pipewire::keys::MEDIA_ROLE => "Screen",
+            },
+        )
+        .map_err(|e| format!("Stream::new: {}", e))?;
+
+        let negotiated_size: Arc<Mutex<(u32, u32)>> = Arc::new(Mutex::new((0, 0)));
+        let size_for_format = negotiated_size.clone();
+        let size_for_process = negotiated_size.clone();
+        let emit_app = app.clone();
+        let fps_interval_ms = 1000u128 / fps.clamp(1, 60) as u128;
+        let last_frame = Arc::new(Mutex::new(std::time::Instant::now()));
+
+        let _listener = stream
+            .add_local_listener::<()>()
+            .param_changed(move |_, _, id, pod| {
+                // SPA_PARAM_Format (4; 3 is EnumFormat) — pull the negotiated
+                // frame size out so the encoder below knows how to slice the
+                // raw buffer (the portal picks the resolution, not us).
+                if id != pipewire::spa::param::ParamType::Format.as_raw() {
+                    return;
+                }
+                let Some(pod) = pod else { return };
+                let mut info = VideoInfoRaw::new();
+                if info.parse(pod).is_ok() {
+                    let size = info.size();
+                    *size_for_format.lock().unwrap() = (size.width, size.height);
+                }
+            })
+            .process(move |stream, _| {
+                let Some(mut buffer) = stream.dequeue_buffer() else {
+                    return;
+                };
+                let (width, height) = *size_for_process.lock().unwrap();
+                if width == 0 || height == 0 {
+                    return;
+                }
+
+                {
+                    let mut last = last_frame.lock().unwrap();
+                    if last.elapsed().as_millis() < fps_interval_ms {
+                        return;
+                    }
+                    *last = std::time::Instant::now();
+                }
+
+                let Some(data) = buffer.datas_mut().first_mut() else {
+                    return;
+                };
+                let Some(raw) = data.data() else { return };
+
+                // Portal/PipeWire monitor streams are BGRx (no alpha) on
+                // every compositor that implements this portal today.
+                let pixel_count = (width * height) as usize;
+                let mut rgb = Vec::with_capacity(pixel_count * 3);
+                for px in raw.chunks_exact(4).take(pixel_count) {
+                    rgb.push(px[2]);
+                    rgb.push(px[1]);
+                    rgb.push(px[0]);
+                }
+                if rgb.len() < pixel_count * 3 {
+                    return;
+                }
+
+                let image = turbojpeg::Image {
+                    pixels: rgb.as_slice(),
+                    width: width as usize,
+                    pitch: width as usize * 3,
+                    height: height as usize,
+                    format: turbojpeg::PixelFormat::RGB,
+                };
+                let Ok(jpeg) = turbojpeg::compress(image, 90, turbojpeg::Subsamp::Sub2x2) else {
+                    return;
+                };
+
+                let timestamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64()
+                    * 1000.0;
+
+                let payload = FramePayload {
+                    data: base64::engine::general_purpose::STANDARD.encode(&*jpeg),
+                    width,
+                    height,
+                    timestamp,
+                    scale_factor: 1.0, // the portal doesn't expose per-monitor DPI
+                };
+                let _ = emit_app.emit("capture-frame", &payload);
+            })
+            .register()
+            .map_err(|e| format!("register listener: {}", e))?;
+
+        let format_bytes = video_format_pod(fps)?;
+        let format_pod = pipewire::spa::pod::Pod::from_bytes(&format_bytes)
+            .ok_or("build format pod")?;
+        stream
+            .connect(
+                pipewire::spa::utils::Direction::Input,
+                Some(node_id),
+                StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+                &mut [format_pod],
+            )
+            .map_err(|e| format!("Stream::connect: {}", e))?;
+
+        // PipeWire's `MainLoop` has no "run until predicate" hook, so pump
+        // it in short bursts and check `stop_flag` between them instead of
+        // calling `run()`.
+        while !stop_flag.load(Ordering::SeqCst) {
+            mainloop.loop_().iterate(std::time::Duration::from_millis(100));
+        }
+
+        Ok(())
+    }
+
+    fn spawn_capture_thread(
+        app: AppHandle,
+        node_id: u32,
+        fps: u32,
+        stop_flag: Arc<AtomicBool>,
+    ) -> std::thread::JoinHandle<()> {
+        std::thread::spawn(move || {
+            if let Err(e) = run_pipewire_capture(app.clone(), node_id, fps, stop_flag) {
+                eprintln!("[PipeWire] capture stream error: {}", e);
+            }
+            let _ = app.emit("capture-stopped", ());
+        })
+    }
+
+    pub(super) async fn enumerate_targets() -> Result<Vec<CaptureTarget>, String> {
+        // The portal's system picker does the actual window/monitor
+        // selection at `start()` time — there's no API to enumerate
+        // sources up front the way WGC lets us walk HWNDs/monitors. These
+        // synthetic entries just give the existing picker UI something to
+        // list; `target_id` only narrows the picker to monitors vs windows.
+        Ok(vec![
+            CaptureTarget {
+                id: "monitor:portal".to_string(),
+                title: "Entire Screen (choose in system picker)".to_string(),
+                target_type: "monitor".to_string(),
+                process_name: String::new(),
+                process_id: 0,
+                width: 0,
+                height: 0,
+                thumbnail: String::new(),
+                scale_factor: 1.0,
+                x: 0,
+                y: 0,
+                refresh_hz: 0,
+            },
+            CaptureTarget {
+                id: "window:portal".to_string(),
+                title: "A Window (choose in system picker)".to_string(),
+                target_type: "window".to_string(),
+                process_name: String::new(),
+                process_id: 0,
+                width: 0,
+                height: 0,
+                thumbnail: String::new(),
+                scale_factor: 1.0,
+                x: 0,
+                y: 0,
+                refresh_hz: 0,
+            },
+        ])
+    }
+
+    pub(super) async fn start(app: AppHandle, target_id: String, fps: u32) -> Result<(), String> {
+        if CAPTURE_SESSION.lock().unwrap().is_some() {
+            return Err("Capture already running".into());
+        }
+
+        let proxy = Screencast::new()
+            .await
+            .map_err(|e| format!("connect to portal: {}", e))?;
+        let portal_session = proxy
+            .create_session()
+            .await
+            .map_err(|e| format!("create portal session: {}", e))?;
+        let node_id = select_and_start(&proxy, &portal_session, &target_id).await?;
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let thread = spawn_capture_thread(app.clone(), node_id, fps, stop_flag.clone());
+
+        *CAPTURE_SESSION.lock().unwrap() = Some(LinuxCaptureSession {
+            portal_session,
+            stop_flag,
+            thread,
+        });
+        let _ = app.emit("capture-started", ());
+        Ok(())
+    }
+
+    pub(super) async fn stop() -> Result<(), String> {
+        let Some(session) = CAPTURE_SESSION.lock().unwrap().take() else {
+            return Err("No capture running".into());
+        };
+        session.stop_flag.store(true, Ordering::SeqCst);
+        let _ = session.thread.join();
+        // Dropping the portal session closes it (and the compositor's
+        // screencast indicator) on the portal side.
+        drop(session.portal_session);
+        Ok(())
+    }
+
+    /// Re-run `select_sources`/`start` on the *existing* portal session
+    /// instead of opening a new one, so switching targets doesn't cost the
+    /// user a second permission prompt. Node ids can also change across a
+    /// monitor hotplug — re-negotiating here picks that up too.
+    pub(super) async fn switch_target(
+        app: AppHandle,
+        target_id: String,
+        fps: u32,
+    ) -> Result<(), String> {
+        let Some(mut session) = CAPTURE_SESSION.lock().unwrap().take() else {
+            return Err("No capture running".into());
+        };
+
+        session.stop_flag.store(true, Ordering::SeqCst);
+        if let Err(e) = session.thread.join() {
+            std::panic::resume_unwind(e);
+        }
+
+        let proxy = Screencast::new()
+            .await
+            .map_err(|e| format!("connect to portal: {}", e))?;
+        let node_id = select_and_start(&proxy, &session.portal_session, &target_id).await?;
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let thread = spawn_capture_thread(app, node_id, fps, stop_flag.clone());
+
+        *CAPTURE_SESSION.lock().unwrap() = Some(LinuxCaptureSession {
+            portal_session: session.portal_session,
+            stop_flag,
+            thread,
+        });
+        Ok(())
+    }
+}
+
+// ─── macOS video capture (ScreenCaptureKit) ─────────────────────────────
+//
+// Audio loopback on macOS also has a platform-specific path, but it isn't
+// cpal-backed like the other non-Windows targets: SCK's system-audio tap
+// (macOS 13+) is bundled into the same `SCStream` as the video, so it's
+// wired up inside `start`/`switch_target` below rather than through
+// `stub::CpalLoopback`. The mic path is unaffected and still goes through
+// `stub`'s existing cpal-backed `start_mic_thread`/`stop_mic_thread`.
+#[cfg(target_os = "macos")]
+mod macos_capture {
+    use std::sync::Mutex;
+
+    use base64::Engine;
+    use screencapturekit::cm_sample_buffer::CMSampleBuffer;
+    use screencapturekit::shareable_content::SCShareableContent;
+    use screencapturekit::stream::configuration::SCStreamConfiguration;
+    use screencapturekit::stream::content_filter::SCContentFilter;
+    use screencapturekit::stream::output_trait::SCStreamOutputTrait;
+    use screencapturekit::stream::output_type::SCStreamOutputType;
+    use screencapturekit::stream::SCStream;
+    use tauri::{AppHandle, Emitter};
+
+    use super::{mix_in_mic, AudioPayload, CaptureTarget, FramePayload};
+
+    /// Sentinel prefix on the error string so the frontend can tell "the
+    /// user needs to grant Screen Recording in System Settings" apart from
+    /// every other capture failure — this crate has no typed error enum
+    /// anywhere else, so a distinguishable string is the established way
+    /// to carry that distinction across the command boundary.
+    pub const TCC_PERMISSION_DENIED_PREFIX: &str = "tcc-permission-denied: ";
+
+    struct MacosCaptureSession {
+        stream: SCStream,
+        // Remembered so `switch_target` can reconfigure the stream without
+        // accidentally toggling system-audio capture off — its signature
+        // (matching `platform::switch_capture_target`'s) has no
+        // `capture_audio` parameter of its own.
+        capture_audio: bool,
+    }
+
+    static CAPTURE_SESSION: Mutex<Option<MacosCaptureSession>> = Mutex::new(None);
+
+    /// `SCShareableContent::get` itself fails with a TCC-flavored error the
+    /// moment Screen Recording hasn't been granted for this app, so probing
+    /// it here doubles as the permission check every entry point needs.
+    fn shareable_content() -> Result<SCShareableContent, String> {
+        SCShareableContent::get().map_err(|e| format!("{}{}", TCC_PERMISSION_DENIED_PREFIX, e))
+    }
+
+    fn resolve_filter(target_id: &str) -> Result<SCContentFilter, String> {
+        let content = shareable_content()?;
+
+        if let Some(raw_id) = target_id.strip_prefix("window:") {
+            let window_id: u32 = raw_id
+                .parse()
+                .map_err(|_| format!("invalid window target id: {}", target_id))?;
+            let window = content
+                .windows()
+                .into_iter()
+                .find(|w| w.window_id() == window_id)
+                .ok_or_else(|| "window target is no longer available".to_string())?;
+            Ok(SCContentFilter::new_with_desktop_independent_window(&window))
+        } else if let Some(raw_id) = target_id.strip_prefix("monitor:") {
+            let display_id: u32 = raw_id
+                .parse()
+                .map_err(|_| format!("invalid monitor target id: {}", target_id))?;
+            let display = content
+                .displays()
+                .into_iter()
+                .find(|d| d.display_id() == display_id)
+                .ok_or_else(|| "monitor target is no longer available".to_string())?;
+            Ok(SCContentFilter::new_with_display_excluding_windows(
+                &display,
+                &[],
+            ))
+        } else {
+            Err(format!("unrecognized target id: {}", target_id))
+        }
+    }
+
+    fn stream_configuration(fps: u32, capture_audio: bool) -> SCStreamConfiguration {
+        let mut config = SCStreamConfiguration::new();
+        config.set_minimum_frame_interval_fps(fps.clamp(1, 120));
+        config.set_pixel_format_bgra();
+        config.set_captures_audio(capture_audio);
+        config
+    }
+
+    /// Feeds both the video and (when enabled) system-audio output of an
+    /// `SCStream` into the same `capture-frame`/`capture-audio` events the
+    /// Windows WGC/WASAPI path emits, so the frontend doesn't need to know
+    /// which backend produced them.
+    struct FrameSink {
+        app: AppHandle,
+    }
+
+    impl SCStreamOutputTrait for FrameSink {
+        fn did_output_sample_buffer(&self, sample: CMSampleBuffer, of_type: SCStreamOutputType) {
+            match of_type {
+                SCStreamOutputType::Screen => emit_video_frame(&self.app, &sample),
+                SCStreamOutputType::Audio => emit_audio_samples(&self.app, &sample),
+            }
+        }
+    }
+
+    fn emit_video_frame(app: &AppHandle, sample: &CMSampleBuffer) {
+        let Some(image) = sample.image_buffer() else {
+            return;
+        };
+        let (width, height) = (image.width(), image.height());
+        let Some(rgb) = image.to_rgb() else { return };
+
+        let jpeg_image = turbojpeg::Image {
+            pixels: rgb.as_slice(),
+            width: width as usize,
+            pitch: width as usize * 3,
+            height: height as usize,
+            format: turbojpeg::PixelFormat::RGB,
+        };
+        let Ok(jpeg) = turbojpeg::compress(jpeg_image, 90, turbojpeg::Subsamp::Sub2x2) else {
+            return;
+        };
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+            * 1000.0;
+
+        let payload = FramePayload {
+            data: base64::engine::general_purpose::STANDARD.encode(&*jpeg),
+            width,
+            height,
+            timestamp,
+            scale_factor: 1.0, // SCK already delivers frames at the display's native pixel size
+        };
+        let _ = app.emit("capture-frame", &payload);
+    }
+
+    fn emit_audio_samples(app: &AppHandle, sample: &CMSampleBuffer) {
+        let Some(audio) = sample.audio_buffer_list() else {
+            return;
+        };
+        // Mix in any staged mic audio before emitting, same as the
+        // Windows/Linux loopback paths — otherwise enabling mic capture on
+        // macOS silently does nothing and `MIC_BUFFER` grows unbounded.
+        let (mixed, mix_rate) = mix_in_mic(&audio.samples, audio.sample_rate);
+        let mixed_frames = (mixed.len() / (audio.channels as usize).max(1)) as u32;
+        let payload = AudioPayload {
+            data: mixed,
+            sample_rate: mix_rate,
+            channels: audio.channels,
+            frames: mixed_frames,
+        };
+        let _ = app.emit("capture-audio", &payload);
+    }
+
+    pub(super) async fn enumerate_targets() -> Result<Vec<CaptureTarget>, String> {
+        let content = shareable_content()?;
+        let mut targets = Vec::new();
+
+        for display in content.displays() {
+            targets.push(CaptureTarget {
+                id: format!("monitor:{}", display.display_id()),
+                title: format!("Display {}", display.display_id()),
+                target_type: "monitor".to_string(),
+                process_name: String::new(),
+                process_id: 0,
+                width: display.width(),
+                height: display.height(),
+                thumbnail: String::new(),
+                scale_factor: 1.0,
+                x: 0,
+                y: 0,
+                refresh_hz: 0,
+            });
+        }
+
+        for window in content.windows() {
+            if !window.is_on_screen() {
+                continue;
+            }
+            let owner = window.owning_application();
+            let frame = window.frame();
+            targets.push(CaptureTarget {
+                id: format!("window:{}", window.window_id()),
+                title: window.title().unwrap_or_default(),
+                target_type: "window".to_string(),
+                process_name: owner.as_ref().map(|a| a.application_name()).unwrap_or_default(),
+                process_id: owner.map(|a| a.process_id()).unwrap_or(0),
+                width: frame.width as u32,
+                height: frame.height as u32,
+                thumbnail: String::new(),
+                scale_factor: 1.0,
+                x: frame.x as i32,
+                y: frame.y as i32,
+                refresh_hz: 0,
+            });
+        }
+
+        Ok(targets)
+    }
+
+    pub(super) async fn start(
+        app: AppHandle,
+        target_id: String,
+        fps: u32,
+        capture_audio: bool,
+    ) -> Result<(), String> {
+        if CAPTURE_SESSION.lock().unwrap().is_some() {
+            return Err("Capture already running".into());
+        }
+
+        let filter = resolve_filter(&target_id)?;
+        let config = stream_configuration(fps, capture_audio);
+        let mut stream = SCStream::new(&filter, &config);
+
+        stream.add_output_handler(FrameSink { app: app.clone() }, SCStreamOutputType::Screen);
+        if capture_audio {
+            stream.add_output_handler(FrameSink { app: app.clone() }, SCStreamOutputType::Audio);
+        }
+
+        stream
+            .start_capture()
+            .map_err(|e| format!("failed to start SCStream: {}", e))?;
+
+        *CAPTURE_SESSION.lock().unwrap() = Some(MacosCaptureSession {
+            stream,
+            capture_audio,
+        });
+        let _ = app.emit("capture-started", ());
+        Ok(())
+    }
+
+    pub(super) async fn stop() -> Result<(), String> {
+        let Some(session) = CAPTURE_SESSION.lock().unwrap().take() else {
+            return Err("No capture running".into());
+        };
+        session
+            .stream
+            .stop_capture()
+            .map_err(|e| format!("failed to stop SCStream: {}", e))
+    }
+
+    /// Reconfigures the live stream's content filter via `updateContentFilter`
+    /// instead of stopping and restarting capture, so a target switch costs
+    /// one frame of disruption rather than a full teardown.
+    pub(super) async fn switch_target(target_id: String, fps: u32) -> Result<(), String> {
+        let guard = CAPTURE_SESSION.lock().unwrap();
+        let Some(session) = guard.as_ref() else {
+            return Err("No capture running".into());
+        };
+
+        let filter = resolve_filter(&target_id)?;
+        session
+            .stream
+            .update_content_filter(&filter)
+            .map_err(|e| format!("failed to update content filter: {}", e))?;
+        session
+            .stream
+            .update_configuration(&stream_configuration(fps, session.capture_audio))
+            .map_err(|e| format!("failed to update stream configuration: {}", e))?;
+
+        Ok(())
+    }
+}
+
+// ─── Stub implementations for non-Windows ───────────────────────────
+#[cfg(not(target_os = "windows"))]
+mod stub {
+    use super::{
+        decode_samples, downmix_to_stereo, mix_in_mic, resample_stereo, stage_mic_samples,
+        start_mic_mix, stop_mic_mix, AudioDevice, AudioPayload, CaptureFormat, CaptureTarget,
+        LoopbackSource, MIX_SAMPLE_RATE,
+    };
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tauri::{AppHandle, Emitter};
+
+    /// No-op on platforms without Win32 DPI awareness APIs.
+    pub fn init_dpi_awareness() {}
+
+    // ─── Portable audio loopback (cpal) ───────────────────────────────
+    //
+    // Video capture needs a backend per OS (`linux_capture`,
+    // `macos_capture`), but audio loopback doesn't: cpal's `Device`/`Stream`
+    // abstraction gets us the default output device's monitor/loopback
+    // input on both CoreAudio and ALSA/PulseAudio, feeding the same
+    // `AudioPayload`/`capture-audio` pipeline `run_wasapi_loopback` does on
+    // Windows.
+
+    static AUDIO_STOP_FLAG: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+    static AUDIO_THREAD_HANDLE: Mutex<Option<std::thread::JoinHandle<()>>> = Mutex::new(None);
+
+    pub(crate) struct CpalLoopback {
+        pub device_id: Option<String>,
+        pub format: CaptureFormat,
+    }
+
+    impl LoopbackSource for CpalLoopback {
+        fn start(&self, app: AppHandle, stop_flag: Arc<AtomicBool>) -> Result<(), String> {
+            run_cpal_loopback(app, stop_flag, self.device_id.clone(), self.format.clone())
+        }
+    }
+
+    /// Look up an output device by name, falling back to the default —
+    /// same shape as `find_mic_device` below, just over `output_devices()`.
+    fn find_output_device(host: &cpal::Host, device_id: Option<&str>) -> Option<cpal::Device> {
+        if let Some(id) = device_id {
+            if let Ok(mut devices) = host.output_devices() {
+                if let Some(device) = devices.find(|d| d.name().map(|n| n == id).unwrap_or(false))
+                {
+                    return Some(device);
+                }
+            }
+            println!("[cpal] device '{}' not found, falling back to default", id);
+        }
+        host.default_output_device()
+    }
+
+    fn sample_format_name(fmt: cpal::SampleFormat) -> String {
+        match fmt {
+            cpal::SampleFormat::I16 => "i16".to_string(),
+            _ => "f32".to_string(),
+        }
+    }
+
+    /// Find the monitor-tap config range closest to `requested` (matching
+    /// channel count exactly, clamping the sample rate into whatever range
+    /// the device reports), so a caller's requested format is honored when
+    /// the device can support it instead of always falling back to
+    /// `default_input_config()`.
+    fn pick_capture_config(
+        device: &cpal::Device,
+        requested: &CaptureFormat,
+    ) -> Option<cpal::SupportedStreamConfig> {
+        let configs = device.supported_input_configs().ok()?;
+        let mut best: Option<cpal::SupportedStreamConfig> = None;
+        let mut best_diff = u32::MAX;
+        for range in configs {
+            if range.channels() != requested.channels {
+                continue;
+            }
+            let clamped = requested
+                .sample_rate
+                .clamp(range.min_sample_rate().0, range.max_sample_rate().0);
+            let diff = clamped.abs_diff(requested.sample_rate);
+            if diff < best_diff {
+                best_diff = diff;
+                best = Some(range.with_sample_rate(cpal::SampleRate(clamped)));
+            }
+        }
+        best
+    }
+
+    fn run_cpal_loopback(
+        app: AppHandle,
+        stop_flag: Arc<AtomicBool>,
+        device_id: Option<String>,
+        requested_format: CaptureFormat,
+    ) -> Result<(), String> {
+        let host = cpal::default_host();
+        let device = find_output_device(&host, device_id.as_deref())
+            .ok_or_else(|| "no output device available".to_string())?;
+        let config = match pick_capture_config(&device, &requested_format) {
+            Some(cfg) => cfg,
+            None => device
+                .default_input_config()
+                .map_err(|e| format!("no usable monitor stream config: {}", e))?,
+        };
+
+        let sample_format = config.sample_format();
+        let stream_config: cpal::StreamConfig = config.into();
+        let channels = stream_config.channels as usize;
+        let sample_rate = stream_config.sample_rate.0;
+        let err_fn = |e| eprintln!("[cpal] stream error: {}", e);
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => {
+                let emit_app = app.clone();
+                device.build_input_stream(
+                    &stream_config,
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        let frame_count = data.len() / channels.max(1);
+                        let raw: Vec<u8> = data.iter().flat_map(|s| s.to_le_bytes()).collect();
+                        let samples = decode_samples(&raw, 4, data.len());
+                        let stereo = downmix_to_stereo(&samples, channels, frame_count);
+                        let (mixed, mix_rate) = mix_in_mic(&stereo, sample_rate);
+                        let mixed_frames = (mixed.len() / 2) as u32;
+                        let payload = AudioPayload {
+                            data: mixed,
+                            sample_rate: mix_rate,
+                            channels: 2,
+                            frames: mixed_frames,
+                        };
+                        let _ = emit_app.emit("capture-audio", &payload);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            cpal::SampleFormat::I16 => {
+                let emit_app = app.clone();
+                device.build_input_stream(
+                    &stream_config,
+                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                        let frame_count = data.len() / channels.max(1);
+                        let raw: Vec<u8> = data.iter().flat_map(|s| s.to_le_bytes()).collect();
+                        let samples = decode_samples(&raw, 2, data.len());
+                        let stereo = downmix_to_stereo(&samples, channels, frame_count);
+                        let (mixed, mix_rate) = mix_in_mic(&stereo, sample_rate);
+                        let mixed_frames = (mixed.len() / 2) as u32;
+                        let payload = AudioPayload {
+                            data: mixed,
+                            sample_rate: mix_rate,
+                            channels: 2,
+                            frames: mixed_frames,
+                        };
+                        let _ = emit_app.emit("capture-audio", &payload);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            other => return Err(format!("unsupported cpal sample format: {:?}", other)),
+        }
+        .map_err(|e| format!("failed to build input stream: {}", e))?;
+
+        stream
+            .play()
+            .map_err(|e| format!("failed to start audio stream: {}", e))?;
+
+        // cpal's stream runs its callback on its own internal thread; this
+        // thread just waits for `stop_capture`/`stop_audio_capture` to flip
+        // `stop_flag`, then drops the stream to tear it down.
+        while !stop_flag.load(Ordering::SeqCst) {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        drop(stream);
+
+        Ok(())
+    }
+
+    /// Report the monitor-tap formats cpal can actually negotiate for
+    /// `device_id` (or the default output device), mirroring
+    /// `platform::supported_capture_formats`'s shape but backed by cpal's
+    /// real `supported_input_configs()` range list instead of a single
+    /// fixed mix format.
+    #[tauri::command]
+    pub async fn supported_capture_formats(
+        device_id: Option<String>,
+    ) -> Result<Vec<CaptureFormat>, String> {
+        let host = cpal::default_host();
+        let device = find_output_device(&host, device_id.as_deref())
+            .ok_or_else(|| "no output device available".to_string())?;
+        let configs = device
+            .supported_input_configs()
+            .map_err(|e| format!("query supported configs: {}", e))?;
+
+        Ok(configs
+            .map(|range| CaptureFormat {
+                sample_rate: range.max_sample_rate().0,
+                channels: range.channels(),
+                sample_format: sample_format_name(range.sample_format()),
+            })
+            .collect())
+    }
+
+    // ─── Microphone capture (cpal) ─────────────────────────────────────
+    //
+    // Same shape as `run_cpal_loopback` above, but on the host's default
+    // (or selected) *input* device rather than an output device's monitor
+    // tap. Doesn't emit its own event — stages resampled samples into the
+    // shared mix buffer for `run_cpal_loopback`/`run_wasapi_loopback` to
+    // pick up.
+
+    static MIC_STOP_FLAG: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+    static MIC_THREAD_HANDLE: Mutex<Option<std::thread::JoinHandle<()>>> = Mutex::new(None);
+
+    fn find_mic_device(host: &cpal::Host, device_id: Option<&str>) -> Option<cpal::Device> {
+        if let Some(id) = device_id {
+            if let Ok(mut devices) = host.input_devices() {
+                if let Some(device) = devices.find(|d| d.name().map(|n| n == id).unwrap_or(false))
+                {
+                    return Some(device);
+                }
+            }
+            println!("[Mic] device '{}' not found, falling back to default", id);
+        }
+        host.default_input_device()
+    }
+
+    fn run_mic_capture(
+        app: AppHandle,
+        stop_flag: Arc<AtomicBool>,
+        device_id: Option<String>,
+    ) -> Result<(), String> {
+        let host = cpal::default_host();
+        let device = find_mic_device(&host, device_id.as_deref())
+            .ok_or_else(|| "no input device available".to_string())?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("no usable mic config: {}", e))?;
+
+        let sample_format = config.sample_format();
+        let stream_config: cpal::StreamConfig = config.into();
+        let channels = stream_config.channels as usize;
+        let sample_rate = stream_config.sample_rate.0;
+        let err_fn = |e| eprintln!("[Mic] stream error: {}", e);
+
+        let _ = app.emit("mic-info", format!("Mic: {}Hz {}ch", sample_rate, channels));
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &stream_config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    let frame_count = data.len() / channels.max(1);
+                    let raw: Vec<u8> = data.iter().flat_map(|s| s.to_le_bytes()).collect();
+                    let samples = decode_samples(&raw, 4, data.len());
+                    let stereo = downmix_to_stereo(&samples, channels, frame_count);
+                    let resampled = resample_stereo(&stereo, sample_rate, MIX_SAMPLE_RATE);
+                    stage_mic_samples(&resampled);
+                },
+                err_fn,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &stream_config,
+                move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                    let frame_count = data.len() / channels.max(1);
+                    let raw: Vec<u8> = data.iter().flat_map(|s| s.to_le_bytes()).collect();
+                    let samples = decode_samples(&raw, 2, data.len());
+                    let stereo = downmix_to_stereo(&samples, channels, frame_count);
+                    let resampled = resample_stereo(&stereo, sample_rate, MIX_SAMPLE_RATE);
+                    stage_mic_samples(&resampled);
+                },
+                err_fn,
+                None,
+            ),
+            other => return Err(format!("unsupported cpal sample format: {:?}", other)),
+        }
+        .map_err(|e| format!("failed to build mic input stream: {}", e))?;
+
+        stream
+            .play()
+            .map_err(|e| format!("failed to start mic stream: {}", e))?;
+
+        while !stop_flag.load(Ordering::SeqCst) {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        drop(stream);
+
+        Ok(())
+    }
+
+    fn start_mic_thread(
+        app: AppHandle,
+        device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+    ) {
+        start_mic_mix(mic_gain, loopback_gain);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        *MIC_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
+
+        let handle = std::thread::spawn(move || {
+            if let Err(e) = run_mic_capture(app, stop_flag, device_id) {
+                eprintln!("Mic capture error: {}", e);
+            }
+        });
+        *MIC_THREAD_HANDLE.lock().unwrap() = Some(handle);
+    }
+
+    fn stop_mic_thread() {
+        if let Some(flag) = MIC_STOP_FLAG.lock().unwrap().take() {
+            flag.store(true, Ordering::SeqCst);
+        }
+        if let Some(handle) = MIC_THREAD_HANDLE.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        stop_mic_mix();
+    }
+
+    /// Start system-audio loopback via cpal, on its own or (on Linux) as the
+    /// audio half of `start_capture`. `target_process_id` mirrors the
+    /// Windows signature but is unused — cpal has no per-process
+    /// include/exclude loopback mode, so this always captures everything.
+    #[tauri::command]
+    pub async fn start_audio_capture(
+        app: AppHandle,
+        _target_process_id: u32,
+        audio_device_id: Option<String>,
+        capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+    ) -> Result<(), String> {
+        if AUDIO_THREAD_HANDLE.lock().unwrap().is_some() {
+            return Err("Audio capture already running".into());
+        }
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        *AUDIO_STOP_FLAG.lock().unwrap() = Some(stop_flag.clone());
+
+        let source = CpalLoopback {
+            device_id: audio_device_id,
+            format: capture_format.unwrap_or_default(),
+        };
+        let audio_app = app.clone();
+        let handle = std::thread::spawn(move || {
+            if let Err(e) = source.start(audio_app, stop_flag) {
+                eprintln!("cpal loopback error: {}", e);
+            }
+        });
+        *AUDIO_THREAD_HANDLE.lock().unwrap() = Some(handle);
+
+        if capture_mic {
+            start_mic_thread(app, mic_device_id, mic_gain, loopback_gain);
+        }
+
+        Ok(())
+    }
+
+    /// List output + input devices via cpal, mirroring
+    /// `platform::enumerate_audio_devices`'s WASAPI-backed shape.
+    #[tauri::command]
+    pub async fn enumerate_audio_devices() -> Result<Vec<AudioDevice>, String> {
+        let host = cpal::default_host();
+        let mut devices = Vec::new();
+
+        let default_output_name = host.default_output_device().and_then(|d| d.name().ok());
+        if let Ok(outputs) = host.output_devices() {
+            for device in outputs {
+                let Ok(name) = device.name() else { continue };
+                let is_default = default_output_name.as_deref() == Some(name.as_str());
+                devices.push(AudioDevice {
+                    id: name.clone(),
+                    name,
+                    direction: "render".to_string(),
+                    is_default,
+                });
+            }
+        }
+
+        let default_input_name = host.default_input_device().and_then(|d| d.name().ok());
+        if let Ok(inputs) = host.input_devices() {
+            for device in inputs {
+                let Ok(name) = device.name() else { continue };
+                let is_default = default_input_name.as_deref() == Some(name.as_str());
+                devices.push(AudioDevice {
+                    id: name.clone(),
+                    name,
+                    direction: "capture".to_string(),
+                    is_default,
+                });
+            }
+        }
+
+        Ok(devices)
+    }
+
+    #[tauri::command]
+    pub async fn stop_audio_capture() -> Result<(), String> {
+        if let Some(flag) = AUDIO_STOP_FLAG.lock().unwrap().take() {
+            flag.store(true, Ordering::SeqCst);
+        }
+        if let Some(handle) = AUDIO_THREAD_HANDLE.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        stop_mic_thread();
+        Ok(())
+    }
+
+    // Video capture: Linux (portal + PipeWire, `super::linux_capture`) and
+    // macOS (ScreenCaptureKit, `super::macos_capture`) each have a real
+    // backend; every other non-Windows target is still an honest "not
+    // supported" stub. All branches keep the exact parameter list
+    // `platform`'s Windows implementation uses, per this module's
+    // signature-parity convention.
+    // Owner/session-scoped filtering (`restrict_to_current_session`) is a
+    // Windows Terminal Services concept with no Linux/macOS equivalent —
+    // each session here is already a separate user/compositor instance —
+    // so it's accepted for signature parity and ignored everywhere but
+    // `platform`.
+    #[cfg(target_os = "linux")]
+    #[tauri::command]
+    pub async fn enumerate_capture_targets(
+        _restrict_to_current_session: Option<bool>,
+    ) -> Result<Vec<CaptureTarget>, String> {
+        super::linux_capture::enumerate_targets().await
+    }
+
+    #[cfg(target_os = "macos")]
+    #[tauri::command]
+    pub async fn enumerate_capture_targets(
+        _restrict_to_current_session: Option<bool>,
+    ) -> Result<Vec<CaptureTarget>, String> {
+        super::macos_capture::enumerate_targets().await
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    #[tauri::command]
+    pub async fn enumerate_capture_targets(
+        _restrict_to_current_session: Option<bool>,
+    ) -> Result<Vec<CaptureTarget>, String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tauri::command]
+    pub async fn start_capture(
+        app: tauri::AppHandle,
+        target_id: String,
+        fps: u32,
+        capture_audio: bool,
+        target_process_id: u32,
+        audio_device_id: Option<String>,
+        capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+        _restrict_to_current_session: Option<bool>,
+    ) -> Result<(), String> {
+        super::linux_capture::start(app.clone(), target_id, fps).await?;
+
+        // cpal loopback has no per-process include/exclude mode, so
+        // `target_process_id` is accepted for signature parity but unused
+        // here — same caveat as `start_audio_capture` above.
+        if capture_audio {
+            start_audio_capture(
+                app,
+                target_process_id,
+                audio_device_id,
+                capture_format,
+                capture_mic,
+                mic_device_id,
+                mic_gain,
+                loopback_gain,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    // ScreenCaptureKit bundles system audio into the same stream as video
+    // (when `capture_audio` is set), so unlike the Linux branch above this
+    // doesn't route through `start_audio_capture`/cpal at all —
+    // `target_process_id`/`audio_device_id`/`capture_format` have no SCK
+    // equivalent (there's no per-process tap or device picker, just "the
+    // system mix"), so they're accepted for signature parity and ignored.
+    // The mic path is still cpal-backed and identical to the Linux branch.
+    #[cfg(target_os = "macos")]
+    #[tauri::command]
+    pub async fn start_capture(
+        app: tauri::AppHandle,
+        target_id: String,
+        fps: u32,
+        capture_audio: bool,
+        _target_process_id: u32,
+        _audio_device_id: Option<String>,
+        _capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+        _restrict_to_current_session: Option<bool>,
+    ) -> Result<(), String> {
+        super::macos_capture::start(app.clone(), target_id, fps, capture_audio).await?;
+
+        if capture_mic {
+            start_mic_thread(app, mic_device_id, mic_gain, loopback_gain);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    #[tauri::command]
+    pub async fn start_capture(
+        _app: tauri::AppHandle,
+        _target_id: String,
+        _fps: u32,
+        _capture_audio: bool,
+        _target_process_id: u32,
+        _audio_device_id: Option<String>,
+        _capture_format: Option<CaptureFormat>,
+        _capture_mic: bool,
+        _mic_device_id: Option<String>,
+        _mic_gain: f32,
+        _loopback_gain: f32,
+        _restrict_to_current_session: Option<bool>,
+    ) -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tauri::command]
+    pub async fn stop_capture() -> Result<(), String> {
+        let video = super::linux_capture::stop().await;
+        stop_audio_capture().await?;
+        video
+    }
+
+    #[cfg(target_os = "macos")]
+    #[tauri::command]
+    pub async fn stop_capture() -> Result<(), String> {
+        stop_mic_thread();
+        super::macos_capture::stop().await
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    #[tauri::command]
+    pub async fn stop_capture() -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tauri::command]
+    pub async fn switch_capture_target(
+        app: tauri::AppHandle,
+        target_id: String,
+        fps: u32,
+        target_process_id: u32,
+        audio_device_id: Option<String>,
+        capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+    ) -> Result<(), String> {
+        super::linux_capture::switch_target(app.clone(), target_id, fps).await?;
+
+        stop_audio_capture().await?;
+        start_audio_capture(
+            app,
+            target_process_id,
+            audio_device_id,
+            capture_format,
+            capture_mic,
+            mic_device_id,
+            mic_gain,
+            loopback_gain,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Reconfigures the live `SCStream`'s content filter instead of
+    /// stopping and restarting capture, so switching targets doesn't cost
+    /// the stream teardown and restart the Linux portal path still does
+    /// (it keeps the portal session but replaces the PipeWire stream).
+    #[cfg(target_os = "macos")]
+    #[tauri::command]
+    pub async fn switch_capture_target(
+        app: tauri::AppHandle,
+        target_id: String,
+        fps: u32,
+        _target_process_id: u32,
+        _audio_device_id: Option<String>,
+        _capture_format: Option<CaptureFormat>,
+        capture_mic: bool,
+        mic_device_id: Option<String>,
+        mic_gain: f32,
+        loopback_gain: f32,
+    ) -> Result<(), String> {
+        super::macos_capture::switch_target(target_id, fps).await?;
+
+        stop_mic_thread();
+        if capture_mic {
+            start_mic_thread(app, mic_device_id, mic_gain, loopback_gain);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    #[tauri::command]
+    pub async fn switch_capture_target(
+        _app: tauri::AppHandle,
+        _target_id: String,
+        _fps: u32,
+        _target_process_id: u32,
+        _audio_device_id: Option<String>,
+        _capture_format: Option<CaptureFormat>,
+        _capture_mic: bool,
+        _mic_device_id: Option<String>,
+        _mic_gain: f32,
+        _loopback_gain: f32,
+    ) -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[derive(serde::Deserialize, Clone)]
+    pub struct RecordingOptions {
+        pub codec: String,
+        pub bitrate_kbps: u32,
+        pub container: String,
+    }
+
+    #[tauri::command]
+    pub async fn start_recording(
+        _app: tauri::AppHandle,
+        _output_path: String,
+        _options: RecordingOptions,
+    ) -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[tauri::command]
+    pub async fn stop_recording() -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[tauri::command]
+    pub async fn start_region_capture(
+        _app: tauri::AppHandle,
+        _target_id: String,
+        _x: u32,
+        _y: u32,
+        _w: u32,
+        _h: u32,
+        _fps: u32,
+    ) -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[tauri::command]
+    pub async fn snapshot(_format: String, _output_path: String) -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[tauri::command]
+    pub async fn snapshot_to_clipboard() -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[tauri::command]
+    pub async fn start_target_watch(_app: tauri::AppHandle) -> Result<(), String> {
+        Err("Native capture is only supported on Windows".into())
+    }
+
+    #[tauri::command]
+    pub async fn stop_target_watch() -> Result<(), String> {
         Err("Native capture is only supported on Windows".into())
     }
 }
@@ -1,9 +1,91 @@
 mod capture;
+mod network_config;
+mod popup_state;
+mod tray;
 
 use std::sync::atomic::{AtomicU32, Ordering};
 
 static POPUP_COUNTER: AtomicU32 = AtomicU32::new(0);
 
+/// Keep only characters the window-labeling runtime accepts
+/// (`[alphanumeric-_/:]`); anything else becomes `_`. The generated
+/// `popup-{n}` label is always valid today, but this is a safety net
+/// against a future label scheme (or a stray character from wherever `n`
+/// comes from) silently producing an unusable label.
+fn sanitize_label(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | ':') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "popup".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn next_popup_label() -> String {
+    let n = POPUP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    sanitize_label(&format!("popup-{}", n))
+}
+
+/// Persist geometry/always-on-top for `label` under `url`'s origin key
+/// whenever the popup moves, resizes, or is about to close. The actual
+/// write is debounced (see `popup_state::save_geometry_debounced`) since
+/// `Moved`/`Resized` fire continuously while the user drags or resizes.
+fn attach_geometry_persistence(
+    app: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+    label: String,
+    url: url::Url,
+) {
+    use tauri::Manager;
+    let persist_app = app.clone();
+    window.on_window_event(move |event| {
+        let geometry_event = matches!(
+            event,
+            tauri::WindowEvent::Moved(_)
+                | tauri::WindowEvent::Resized(_)
+                | tauri::WindowEvent::CloseRequested { .. }
+        );
+        if !geometry_event {
+            return;
+        }
+        let Some(win) = persist_app.get_webview_window(&label) else {
+            return;
+        };
+        let (Ok(pos), Ok(size), Ok(scale), Ok(always_on_top)) = (
+            win.outer_position(),
+            win.inner_size(),
+            win.scale_factor(),
+            win.is_always_on_top(),
+        ) else {
+            return;
+        };
+        // Stored in logical units, as the builder's `position`/`inner_size`
+        // take them when the popup is reopened.
+        let pos = pos.to_logical::<f64>(scale);
+        let size = size.to_logical::<f64>(scale);
+        popup_state::save_geometry_debounced(
+            persist_app.clone(),
+            url.clone(),
+            popup_state::PopupGeometry {
+                x: pos.x,
+                y: pos.y,
+                width: size.width,
+                height: size.height,
+                always_on_top,
+            },
+        );
+    });
+}
+
 #[tauri::command]
 async fn set_popout_always_on_top(
     app: tauri::AppHandle,
@@ -20,6 +102,8 @@ async fn set_popout_always_on_top(
 }
 
 pub fn run() {
+    capture::init_dpi_awareness();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_http::init())
@@ -27,15 +111,35 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_window_state::Builder::default().build())
         .invoke_handler(tauri::generate_handler![
+            capture::get_capture_capabilities,
             capture::enumerate_capture_targets,
             capture::start_capture,
             capture::stop_capture,
             capture::switch_capture_target,
+            capture::start_region_capture,
+            capture::start_target_watch,
+            capture::stop_target_watch,
+            capture::enumerate_audio_devices,
+            capture::supported_capture_formats,
+            capture::start_audio_capture,
+            capture::stop_audio_capture,
+            capture::start_recording,
+            capture::stop_recording,
+            capture::snapshot,
+            capture::snapshot_to_clipboard,
+            capture::pick_recording_destination,
+            capture::pick_capture_import,
+            tray::refresh_tray_targets,
+            popup_state::reset_popup_layout,
+            network_config::set_network_config,
             set_popout_always_on_top,
         ])
         .setup(|app| {
             use tauri::webview::{NewWindowResponse, WebviewWindowBuilder};
-            use tauri::WebviewUrl;
+            use tauri::{Emitter, Manager, WebviewUrl};
+
+            network_config::load(app.handle());
+            tray::build_tray(app.handle())?;
 
             let app_handle = app.handle().clone();
 
@@ -44,30 +148,88 @@ pub fn run() {
                 .inner_size(1280.0, 800.0)
                 .min_inner_size(960.0, 600.0)
                 .on_new_window(move |url, features| {
-                    let n = POPUP_COUNTER.fetch_add(1, Ordering::Relaxed);
-                    let label = format!("popup-{}", n);
-
-                    let mut builder = WebviewWindowBuilder::new(
-                        &app_handle,
-                        &label,
-                        WebviewUrl::External(url),
-                    )
-                    .title("Nexus VC")
-                    .always_on_top(true);
-
-                    if let Some(size) = features.size() {
-                        builder = builder.inner_size(size.width, size.height);
-                    } else {
-                        builder = builder.inner_size(480.0, 640.0);
-                    }
+                    let saved = popup_state::geometry_for(&app_handle, &url);
+                    let always_on_top = saved.map(|g| g.always_on_top).unwrap_or(true);
+                    let net_cfg = network_config::current();
 
-                    if let Some(pos) = features.position() {
-                        builder = builder.position(pos.x, pos.y);
-                    }
+                    let build = |label: &str| -> tauri::Result<tauri::WebviewWindow> {
+                        let mut builder = WebviewWindowBuilder::new(
+                            &app_handle,
+                            label,
+                            WebviewUrl::External(url.clone()),
+                        )
+                        .title("Nexus VC")
+                        .always_on_top(always_on_top);
+
+                        if let Some(ua) = &net_cfg.user_agent {
+                            builder = builder.user_agent(ua);
+                        }
+                        if let Some(proxy) = &net_cfg.proxy_url {
+                            if let Ok(proxy_url) = proxy.parse() {
+                                builder = builder.proxy_url(proxy_url);
+                            }
+                        }
+
+                        if let Some(g) = saved {
+                            builder = builder.inner_size(g.width, g.height).position(g.x, g.y);
+                        } else if let Some(size) = features.size() {
+                            builder = builder.inner_size(size.width, size.height);
+                        } else {
+                            builder = builder.inner_size(480.0, 640.0);
+                        }
+
+                        if saved.is_none() {
+                            if let Some(pos) = features.position() {
+                                builder = builder.position(pos.x, pos.y);
+                            }
+                        }
+
+                        builder.build()
+                    };
+
+                    let label = next_popup_label();
+                    match build(&label) {
+                        Ok(window) => {
+                            attach_geometry_persistence(&app_handle, &window, label, url);
+                            NewWindowResponse::Create { window }
+                        }
+                        Err(e) => {
+                            let _ = app_handle.emit(
+                                "popup-error",
+                                serde_json::json!({
+                                    "url": url.to_string(),
+                                    "error": e.to_string(),
+                                    "label": label,
+                                }),
+                            );
 
-                    match builder.build() {
-                        Ok(window) => NewWindowResponse::Create { window },
-                        Err(_) => NewWindowResponse::Deny,
+                            // Retry once with a freshly incremented counter —
+                            // this recovers from a label collision instead of
+                            // permanently denying the window.
+                            let retry_label = next_popup_label();
+                            match build(&retry_label) {
+                                Ok(window) => {
+                                    attach_geometry_persistence(
+                                        &app_handle,
+                                        &window,
+                                        retry_label,
+                                        url,
+                                    );
+                                    NewWindowResponse::Create { window }
+                                }
+                                Err(e2) => {
+                                    let _ = app_handle.emit(
+                                        "popup-error",
+                                        serde_json::json!({
+                                            "url": url.to_string(),
+                                            "error": e2.to_string(),
+                                            "label": retry_label,
+                                        }),
+                                    );
+                                    NewWindowResponse::Deny
+                                }
+                            }
+                        }
                     }
                 })
                 .build()?;
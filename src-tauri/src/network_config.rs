@@ -0,0 +1,77 @@
+/*
+Copyright 2025 Nexus Contributors
+
+SPDX-License-Identifier: AGPL-3.0-only OR GPL-3.0-only OR LicenseRef-Element-Commercial
+Please see LICENSE files in the repository root for full details.
+*/
+
+//! App-level network configuration: an optional proxy and a custom
+//! User-Agent, applied to popup webviews.
+//!
+//! The config is loaded once at `setup` and can be changed at runtime via
+//! `set_network_config`, which affects every popup opened afterwards
+//! (`lib.rs` reads `current()` when building each popup's webview). It does
+//! *not* reach `tauri_plugin_http`: that plugin's reqwest client is built
+//! when `.plugin()` registers it, before `setup` ever runs, and reqwest
+//! resolves proxy config once at client construction — setting
+//! `HTTPS_PROXY`/`HTTP_PROXY` afterwards (here, or via `set_network_config`)
+//! has no effect on it. Rerouting the HTTP plugin's traffic would need a
+//! custom request hook or rebuilding the plugin at runtime; neither is
+//! wired up, so don't rely on this for plugin requests.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const CONFIG_FILE: &str = "network-config.json";
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct NetworkConfig {
+    pub proxy_url: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+static NETWORK_CONFIG: Mutex<Option<NetworkConfig>> = Mutex::new(None);
+
+fn config_file(app: &AppHandle) -> Option<std::path::PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// Read the saved config (if any) into memory so `current()` has it ready
+/// before the first popup is built. Call once from `setup`.
+pub fn load(app: &AppHandle) {
+    let cfg = config_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|s| serde_json::from_str::<NetworkConfig>(&s).ok())
+        .unwrap_or_default();
+    *NETWORK_CONFIG.lock().unwrap() = Some(cfg);
+}
+
+/// Current config, used when building each new popup webview.
+pub fn current() -> NetworkConfig {
+    NETWORK_CONFIG.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// Save the config for new popups (and future launches) to pick up. Popups
+/// already open keep whatever they were built with — a proxy/user-agent
+/// change only takes effect for popups opened after this call.
+#[tauri::command]
+pub async fn set_network_config(
+    app: AppHandle,
+    proxy_url: Option<String>,
+    user_agent: Option<String>,
+) -> Result<(), String> {
+    let cfg = NetworkConfig { proxy_url, user_agent };
+
+    if let Some(path) = config_file(&app) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())?;
+    }
+
+    *NETWORK_CONFIG.lock().unwrap() = Some(cfg);
+    Ok(())
+}
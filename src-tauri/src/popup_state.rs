@@ -0,0 +1,148 @@
+/*
+Copyright 2025 Nexus Contributors
+
+SPDX-License-Identifier: AGPL-3.0-only OR GPL-3.0-only OR LicenseRef-Element-Commercial
+Please see LICENSE files in the repository root for full details.
+*/
+
+//! Per-popup window geometry persistence, keyed by the external URL's
+//! origin rather than by window label.
+//!
+//! `tauri_plugin_window_state` only persists the `main` window: popup
+//! labels are a fresh `popup-{n}` counter every launch, so the plugin has
+//! no stable key to save against. This module keys on a hash of the
+//! popup's scheme+host+path instead, so reopening the same VC room
+//! restores its last geometry even though the label is different.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const STATE_FILE: &str = "popup-state.json";
+
+/// Per-key epoch counter backing `save_geometry_debounced` — lets a
+/// superseded save notice it's stale and skip its write.
+static PENDING_SAVES: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+
+const SAVE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct PopupGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub always_on_top: bool,
+}
+
+impl Default for PopupGeometry {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 480.0,
+            height: 640.0,
+            always_on_top: true,
+        }
+    }
+}
+
+/// Derive a stable key from a popup URL's scheme+host+path, ignoring
+/// query/fragment so room tokens in the URL don't fragment the saved
+/// geometry per-join.
+pub fn key_for_url(url: &url::Url) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    url.scheme().hash(&mut hasher);
+    url.host_str().unwrap_or("").hash(&mut hasher);
+    url.path().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn state_file(app: &AppHandle) -> Option<std::path::PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(STATE_FILE))
+}
+
+fn load_all(app: &AppHandle) -> HashMap<String, PopupGeometry> {
+    let Some(path) = state_file(app) else {
+        return HashMap::new();
+    };
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_all(app: &AppHandle, map: &HashMap<String, PopupGeometry>) {
+    let Some(path) = state_file(app) else { return };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(map) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Look up the saved geometry for a popup URL, if any was ever recorded.
+pub fn geometry_for(app: &AppHandle, url: &url::Url) -> Option<PopupGeometry> {
+    load_all(app).get(&key_for_url(url)).copied()
+}
+
+/// Persist the current geometry for a popup URL.
+pub fn save_geometry(app: &AppHandle, url: &url::Url, geometry: PopupGeometry) {
+    let mut all = load_all(app);
+    all.insert(key_for_url(url), geometry);
+    save_all(app, &all);
+}
+
+/// Debounced version of `save_geometry` for `Moved`/`Resized` window
+/// events, which fire continuously while a popup is being dragged or
+/// resized. Writing `popup-state.json` on every one of those (on the
+/// window-event callback thread, no less) would stutter the drag and
+/// scale I/O with total popup count rather than with the one popup
+/// actually moving. Instead, bump this popup's epoch and hand the
+/// read-modify-write off to a background thread after a short quiet
+/// period; if another move/resize supersedes it before the quiet period
+/// elapses, the stale write notices and skips itself.
+pub fn save_geometry_debounced(app: AppHandle, url: url::Url, geometry: PopupGeometry) {
+    let key = key_for_url(&url);
+    let epoch = {
+        let mut pending = PENDING_SAVES.lock().unwrap();
+        let pending = pending.get_or_insert_with(HashMap::new);
+        let next = pending.get(&key).copied().unwrap_or(0) + 1;
+        pending.insert(key.clone(), next);
+        next
+    };
+
+    std::thread::spawn(move || {
+        std::thread::sleep(SAVE_DEBOUNCE);
+
+        let current = PENDING_SAVES
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|pending| pending.get(&key).copied());
+        if current != Some(epoch) {
+            return; // a later move/resize superseded this save
+        }
+
+        let mut all = load_all(&app);
+        all.insert(key, geometry);
+        save_all(&app, &all);
+    });
+}
+
+/// Clear all stored popup geometry, so every popup reopens at the default
+/// size/position until it's moved/resized again.
+#[tauri::command]
+pub async fn reset_popup_layout(app: AppHandle) -> Result<(), String> {
+    let Some(path) = state_file(&app) else {
+        return Ok(());
+    };
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+/*
+Copyright 2025 Nexus Contributors
+
+SPDX-License-Identifier: AGPL-3.0-only OR GPL-3.0-only OR LicenseRef-Element-Commercial
+Please see LICENSE files in the repository root for full details.
+*/
+
+//! System tray icon exposing capture controls without requiring the main
+//! window to be focused.
+//!
+//! The tray menu is rebuilt whenever the capture target list changes
+//! (`refresh_tray_targets`) and the tooltip/toggle label track capture
+//! state via the `capture-started`/`capture-stopped` events the `capture`
+//! module already emits.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Emitter, Listener, Manager, Wry};
+
+use crate::capture;
+
+static TRAY_CAPTURE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Last target list `refresh_tray_targets` built the menu from, so
+/// `set_capture_state` can rebuild the toggle/tooltip without wiping the
+/// "Switch Target" submenu back to empty in between refreshes.
+static LAST_TARGETS: Mutex<Vec<capture::CaptureTarget>> = Mutex::new(Vec::new());
+
+const MENU_ID_TOGGLE_CAPTURE: &str = "toggle-capture";
+const MENU_ID_CLOSE_POPUPS: &str = "close-all-popups";
+const MENU_ID_QUIT: &str = "quit";
+const TARGET_MENU_PREFIX: &str = "target:";
+
+/// Holds the live tray icon so later code (event listeners, the
+/// `refresh_tray_targets` command) can update its menu/tooltip in place.
+struct TrayHandle(TrayIcon<Wry>);
+
+/// Build and attach the tray icon. Call once from `setup`.
+pub fn build_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app, &[])?;
+
+    let tray = tauri::tray::TrayIconBuilder::with_id("main-tray")
+        .tooltip("Nexus — capture stopped")
+        .menu(&menu)
+        .on_menu_event(handle_menu_event)
+        .build(app)?;
+
+    app.manage(TrayHandle(tray));
+
+    let app_handle = app.clone();
+    app.listen("capture-started", move |_event| {
+        set_capture_state(&app_handle, true);
+    });
+    let app_handle = app.clone();
+    app.listen("capture-stopped", move |_event| {
+        set_capture_state(&app_handle, false);
+    });
+
+    // Populate the target submenu once at startup.
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = refresh_tray_targets(app_handle).await;
+    });
+
+    Ok(())
+}
+
+fn build_menu(app: &AppHandle, targets: &[capture::CaptureTarget]) -> tauri::Result<Menu<Wry>> {
+    let running = TRAY_CAPTURE_RUNNING.load(Ordering::SeqCst);
+
+    let toggle_capture = MenuItem::with_id(
+        app,
+        MENU_ID_TOGGLE_CAPTURE,
+        if running { "Stop Capture" } else { "Start Capture" },
+        true,
+        None::<&str>,
+    )?;
+
+    let target_items: Vec<MenuItem<Wry>> = targets
+        .iter()
+        .map(|target| {
+            MenuItem::with_id(
+                app,
+                format!("{}{}", TARGET_MENU_PREFIX, target.id),
+                &target.title,
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<_>>()?;
+    let target_refs: Vec<&dyn tauri::menu::IsMenuItem<Wry>> = target_items
+        .iter()
+        .map(|item| item as &dyn tauri::menu::IsMenuItem<Wry>)
+        .collect();
+    let switch_target = Submenu::with_items(app, "Switch Target", true, &target_refs)?;
+
+    let close_popups = MenuItem::with_id(
+        app,
+        MENU_ID_CLOSE_POPUPS,
+        "Close All Popups",
+        true,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, MENU_ID_QUIT, "Quit Nexus", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &toggle_capture,
+            &switch_target,
+            &PredefinedMenuItem::separator(app)?,
+            &close_popups,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )
+}
+
+fn set_capture_state(app: &AppHandle, running: bool) {
+    TRAY_CAPTURE_RUNNING.store(running, Ordering::SeqCst);
+    if let Some(handle) = app.try_state::<TrayHandle>() {
+        let _ = handle.0.set_tooltip(Some(if running {
+            "Nexus — capturing"
+        } else {
+            "Nexus — capture stopped"
+        }));
+    }
+    // Re-render the toggle label, rebuilding the target submenu from the
+    // last enumeration rather than an empty list — changing capture state
+    // doesn't change which targets exist, so the submenu shouldn't go blank.
+    let targets = LAST_TARGETS.lock().unwrap().clone();
+    if let Ok(menu) = build_menu(app, &targets) {
+        if let Some(handle) = app.try_state::<TrayHandle>() {
+            let _ = handle.0.set_menu(Some(menu));
+        }
+    }
+}
+
+/// Rebuild the "Switch Target" submenu from a fresh enumeration. Exposed so
+/// the frontend can call this whenever its own picker list changes.
+#[tauri::command]
+pub async fn refresh_tray_targets(app: AppHandle) -> Result<(), String> {
+    let targets = capture::enumerate_capture_targets(None).await?;
+    let menu = build_menu(&app, &targets).map_err(|e| e.to_string())?;
+    if let Some(handle) = app.try_state::<TrayHandle>() {
+        handle.0.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+    }
+    *LAST_TARGETS.lock().unwrap() = targets;
+    Ok(())
+}
+
+fn close_all_popups(app: &AppHandle) {
+    for (label, window) in app.webview_windows() {
+        if label.starts_with("popup-") {
+            let _ = window.close();
+        }
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
+    let id = event.id().as_ref();
+
+    if let Some(target_id) = id.strip_prefix(TARGET_MENU_PREFIX) {
+        let app_handle = app.clone();
+        let target_id = target_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            // Quick-switch from the tray uses sane defaults; the in-app
+            // picker is still the place to tune fps/process filtering.
+            let _ = capture::switch_capture_target(
+                app_handle.clone(),
+                target_id,
+                30,
+                0,
+                None,
+                None,
+                false,
+                None,
+                1.0,
+                1.0,
+            )
+            .await;
+        });
+        return;
+    }
+
+    match id {
+        MENU_ID_TOGGLE_CAPTURE => {
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if TRAY_CAPTURE_RUNNING.load(Ordering::SeqCst) {
+                    let _ = capture::stop_capture().await;
+                } else {
+                    let _ = app_handle.emit("tray-start-capture-requested", ());
+                }
+            });
+        }
+        MENU_ID_CLOSE_POPUPS => close_all_popups(app),
+        MENU_ID_QUIT => app.exit(0),
+        _ => {}
+    }
+}